@@ -0,0 +1,445 @@
+use crate::diagnostics::DiagType;
+use crate::file_utils::is_url;
+use crate::gsn::SiblingOrder;
+use crate::gsn::instantiation::resolve_relative_to;
+use anyhow::{Context, Result, anyhow};
+use clap::ArgMatches;
+use clap::parser::ValueSource;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+///
+/// Default name of the project file.
+///
+pub const PROJECT_FILE: &str = "gsn2x.yaml";
+
+///
+/// Options as they can be provided in a project file.
+/// The names of the attributes are the long names of the command line options.
+///
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ProjectFile {
+    inputs: Option<Vec<String>>,
+    include: Option<Vec<String>>,
+    check: Option<bool>,
+    exclude: Option<Vec<String>>,
+    warn_dialectic: Option<bool>,
+    extended_check: Option<bool>,
+    diagnostics: Option<String>,
+    diagnostics_output: Option<String>,
+    severity: Option<BTreeMap<String, String>>,
+    deny_warnings: Option<bool>,
+    baseline: Option<String>,
+    write_baseline: Option<String>,
+    no_arg: Option<bool>,
+    full: Option<String>,
+    no_full: Option<bool>,
+    arch: Option<String>,
+    no_arch: Option<bool>,
+    evidence: Option<String>,
+    no_evidence: Option<bool>,
+    output_dir: Option<String>,
+    statistics: Option<String>,
+    dump_yaml: Option<String>,
+    instantiate: Option<String>,
+    layer: Option<Vec<String>>,
+    stylesheet: Option<Vec<String>>,
+    embed_css: Option<bool>,
+    mask: Option<Vec<String>>,
+    no_legend: Option<bool>,
+    full_legend: Option<bool>,
+    wrap: Option<u32>,
+    sibling_order: Option<SiblingOrder>,
+}
+
+impl ProjectFile {
+    ///
+    /// Read the project file at `path`.
+    ///
+    /// Relative paths in the project file are relative to the directory of the project file.
+    ///
+    pub fn read(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to open project file {path}."))?;
+        let mut project: ProjectFile = serde_saphyr::from_str(&content)
+            .with_context(|| format!("Failed to parse project file {path}."))?;
+        project.resolve_paths(path);
+        Ok(project)
+    }
+
+    ///
+    /// Resolve the relative paths of files and directories relative to the project file at `path`.
+    /// The names of the views are file names in the output directory and stay as they are.
+    ///
+    fn resolve_paths(&mut self, path: &str) {
+        let resolve = |p: &mut String| *p = resolve_relative_to(path, p);
+        for paths in [&mut self.inputs, &mut self.include].into_iter().flatten() {
+            paths.iter_mut().for_each(resolve);
+        }
+        for p in [
+            &mut self.diagnostics_output,
+            &mut self.baseline,
+            &mut self.write_baseline,
+            &mut self.output_dir,
+            &mut self.statistics,
+            &mut self.dump_yaml,
+            &mut self.instantiate,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            resolve(p);
+        }
+        // Stylesheets given as URL are not resolved
+        self.stylesheet
+            .iter_mut()
+            .flatten()
+            .filter(|s| !is_url(s))
+            .for_each(resolve);
+    }
+
+    ///
+    /// Read the project file given on the command line,
+    /// or the default project file if it exists in the current working directory.
+    ///
+    pub fn from_matches(matches: &ArgMatches) -> Result<Self> {
+        match matches.get_one::<String>("CONFIG") {
+            Some(path) => ProjectFile::read(path),
+            None if Path::new(PROJECT_FILE).exists() => ProjectFile::read(PROJECT_FILE),
+            None => Ok(ProjectFile::default()),
+        }
+    }
+}
+
+///
+/// The configuration merged from the project file and the command line.
+///
+/// Options given on the command line override those of the project file.
+///
+#[derive(Debug, Default)]
+pub struct Config {
+    pub inputs: Vec<String>,
+    pub default_inputs: bool,
+    pub include_directories: Vec<String>,
+    pub check_only: bool,
+    pub excluded_modules: Vec<String>,
+    pub warn_dialectic: bool,
+    pub extended_check: bool,
+    pub diagnostics_format: String,
+    pub diagnostics_output: Option<String>,
+    pub severities: Vec<(String, DiagType)>,
+    pub deny_warnings: bool,
+    pub baseline: Option<String>,
+    pub write_baseline: Option<String>,
+    pub fix: bool,
+    pub format: bool,
+    pub rename: Option<(String, String)>,
+    pub renumber: Option<String>,
+    pub no_argument_view: bool,
+    pub complete_view: Option<String>,
+    pub architecture_view: Option<String>,
+    pub evidence: Option<String>,
+    pub output_directory: String,
+    // Outer option: output requested, inner option: file name or standard output
+    pub statistics: Option<Option<String>>,
+    pub yaml_dump: Option<Option<String>>,
+    pub instantiate: Option<String>,
+    pub layers: Vec<String>,
+    pub stylesheets: Vec<String>,
+    pub embed_stylesheets: bool,
+    pub masked_modules: Vec<String>,
+    pub no_legend: bool,
+    pub full_legend: bool,
+    pub char_wrap: Option<u32>,
+    pub sibling_order: SiblingOrder,
+}
+
+impl Config {
+    ///
+    /// Merge the project file `project` and the command line `matches`.
+    ///
+    pub fn new(matches: &ArgMatches, project: ProjectFile) -> Result<Self> {
+        let severities = match project.severity {
+            Some(severity) if !is_from_command_line(matches, "SEVERITY") => severity
+                .iter()
+                .map(|(check, level)| parse_severity(&format!("{check}={level}")))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| anyhow!(e))?,
+            _ => get_values(matches, "SEVERITY", None),
+        };
+        let diagnostics_format = get_value(matches, "DIAGNOSTICS_FORMAT", project.diagnostics)
+            .unwrap_or_else(|| "text".to_owned());
+        if !["text", "jsonl", "sarif"].contains(&diagnostics_format.as_str()) {
+            return Err(anyhow!(
+                "Unknown diagnostics format {diagnostics_format}. Use either text, jsonl or sarif."
+            ));
+        }
+        // An explicitly provided view on the command line also overrides a disabled view in the project file.
+        let no_full = get_flag(matches, "NO_COMPLETE_VIEW", project.no_full)
+            && !is_from_command_line(matches, "COMPLETE_VIEW");
+        let no_arch = get_flag(matches, "NO_ARCHITECTURE_VIEW", project.no_arch)
+            && !is_from_command_line(matches, "ARCHITECTURE_VIEW");
+        let no_evidence = get_flag(matches, "NO_EVIDENCE", project.no_evidence)
+            && !is_from_command_line(matches, "EVIDENCE");
+
+        Ok(Config {
+            default_inputs: project.inputs.is_none()
+                && matches.value_source("INPUT") == Some(ValueSource::DefaultValue),
+            inputs: get_values(matches, "INPUT", project.inputs),
+            include_directories: get_values(matches, "INCLUDE_DIRECTORIES", project.include),
+            check_only: get_flag(matches, "CHECK_ONLY", project.check),
+            excluded_modules: get_values(matches, "EXCLUDED_MODULE", project.exclude),
+            warn_dialectic: get_flag(matches, "WARN_DIALECTIC", project.warn_dialectic),
+            extended_check: get_flag(matches, "EXTENDED_CHECK", project.extended_check),
+            diagnostics_format,
+            diagnostics_output: get_value(
+                matches,
+                "DIAGNOSTICS_OUTPUT",
+                project.diagnostics_output,
+            ),
+            severities,
+            deny_warnings: get_flag(matches, "DENY_WARNINGS", project.deny_warnings),
+            baseline: get_value(matches, "BASELINE", project.baseline),
+            write_baseline: get_value(matches, "WRITE_BASELINE", project.write_baseline),
+            fix: get_flag(matches, "FIX", None),
+            format: get_flag(matches, "FORMAT", None),
+            rename: matches.get_one::<(String, String)>("RENAME").cloned(),
+            renumber: get_value(matches, "RENUMBER", None),
+            no_argument_view: get_flag(matches, "NO_ARGUMENT_VIEW", project.no_arg),
+            complete_view: get_value(matches, "COMPLETE_VIEW", project.full).filter(|_| !no_full),
+            architecture_view: get_value(matches, "ARCHITECTURE_VIEW", project.arch)
+                .filter(|_| !no_arch),
+            evidence: get_value(matches, "EVIDENCE", project.evidence).filter(|_| !no_evidence),
+            // unwrap ok, since default value provided.
+            output_directory: get_value(matches, "OUTPUT_DIRECTORY", project.output_dir).unwrap(),
+            statistics: get_optional_output(matches, "STATISTICS", project.statistics),
+            yaml_dump: get_optional_output(matches, "YAMLDUMP", project.dump_yaml),
+            instantiate: get_value(matches, "INSTANTIATE", project.instantiate),
+            layers: get_values(matches, "LAYERS", project.layer),
+            stylesheets: get_values(matches, "STYLESHEETS", project.stylesheet),
+            embed_stylesheets: get_flag(matches, "EMBED_CSS", project.embed_css),
+            masked_modules: get_values(matches, "MASKED_MODULE", project.mask),
+            no_legend: get_flag(matches, "NO_LEGEND", project.no_legend)
+                && !is_from_command_line(matches, "FULL_LEGEND"),
+            full_legend: get_flag(matches, "FULL_LEGEND", project.full_legend)
+                && !is_from_command_line(matches, "NO_LEGEND"),
+            char_wrap: get_value(matches, "CHAR_WRAP", project.wrap),
+            sibling_order: get_value(matches, "SIBLING_ORDER", project.sibling_order)
+                .unwrap_or_default(),
+        })
+    }
+}
+
+///
+/// Parse a severity policy entry in the form of CHECK=LEVEL.
+///
+pub fn parse_severity(entry: &str) -> Result<(String, DiagType), String> {
+    let (check, level) = entry
+        .split_once('=')
+        .ok_or_else(|| format!("Expected CHECK=LEVEL, but found {entry}."))?;
+    let is_category = check == "C" || check == "V";
+    let is_check_id = check.len() == 3
+        && (check.starts_with('C') || check.starts_with('V'))
+        && check[1..].chars().all(|c| c.is_ascii_digit());
+    if !is_category && !is_check_id {
+        return Err(format!("Unknown check or category {check}."));
+    }
+    match level {
+        "error" => Ok((check.to_owned(), DiagType::Error)),
+        "warning" => Ok((check.to_owned(), DiagType::Warning)),
+        _ => Err(format!(
+            "Unknown level {level}. Use either error or warning."
+        )),
+    }
+}
+
+///
+/// Parse a rename entry in the form of OLD=NEW.
+///
+pub fn parse_rename(entry: &str) -> Result<(String, String), String> {
+    match entry.split_once('=') {
+        Some((old, new)) if !old.is_empty() && !new.is_empty() => {
+            Ok((old.to_owned(), new.to_owned()))
+        }
+        _ => Err(format!("Expected OLD=NEW, but found {entry}.")),
+    }
+}
+
+///
+/// Parse the order of elements on the same rank.
+///
+pub fn parse_sibling_order(order: &str) -> Result<SiblingOrder, String> {
+    match order {
+        "lexicographic" => Ok(SiblingOrder::Lexicographic),
+        "natural" => Ok(SiblingOrder::Natural),
+        "authored" => Ok(SiblingOrder::Authored),
+        _ => Err(format!(
+            "Unknown order {order}. Use either lexicographic, natural or authored."
+        )),
+    }
+}
+
+fn is_from_command_line(matches: &ArgMatches, id: &str) -> bool {
+    matches.value_source(id) == Some(ValueSource::CommandLine)
+}
+
+///
+/// Get the value of option `id`. The project file is only used if the option is not on the command line.
+///
+fn get_value<T: Clone + Send + Sync + 'static>(
+    matches: &ArgMatches,
+    id: &str,
+    project: Option<T>,
+) -> Option<T> {
+    match project {
+        Some(value) if !is_from_command_line(matches, id) => Some(value),
+        _ => matches.get_one::<T>(id).cloned(),
+    }
+}
+
+///
+/// Get all values of option `id`. The project file is only used if the option is not on the command line.
+///
+fn get_values<T: Clone + Send + Sync + 'static>(
+    matches: &ArgMatches,
+    id: &str,
+    project: Option<Vec<T>>,
+) -> Vec<T> {
+    match project {
+        Some(values) if !is_from_command_line(matches, id) => values,
+        _ => matches
+            .get_many::<T>(id)
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect(),
+    }
+}
+
+///
+/// Get flag `id`. The project file is only used if the flag is not on the command line.
+///
+fn get_flag(matches: &ArgMatches, id: &str, project: Option<bool>) -> bool {
+    match project {
+        Some(value) if !is_from_command_line(matches, id) => value,
+        _ => matches.get_flag(id),
+    }
+}
+
+///
+/// Get an output that is written to a file or, if no file name is given, to standard output.
+///
+fn get_optional_output(
+    matches: &ArgMatches,
+    id: &str,
+    project: Option<String>,
+) -> Option<Option<String>> {
+    if is_from_command_line(matches, id) {
+        Some(matches.get_one::<String>(id).cloned())
+    } else {
+        project.map(Some)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::build_command_options;
+
+    #[test]
+    fn command_line_overrides_project_file() -> Result<()> {
+        let project: ProjectFile = serde_saphyr::from_str(
+            "inputs: [main.gsn.yaml]\nlayer: [l1, l2]\nmask: [M1]\nwrap: 20\nno-full: true\nno-legend: true\nseverity:\n  C01: error\nsibling-order: authored\ninclude: [lib]\n",
+        )?;
+        let matches = build_command_options().try_get_matches_from(vec![
+            "gsn2x",
+            "-l=l3",
+            "-f=full.svg",
+            "-g",
+        ])?;
+        let config = Config::new(&matches, project)?;
+        assert_eq!(config.inputs, vec!["main.gsn.yaml".to_owned()]);
+        assert!(!config.default_inputs);
+        assert_eq!(config.layers, vec!["l3".to_owned()]);
+        assert_eq!(config.masked_modules, vec!["M1".to_owned()]);
+        assert_eq!(config.char_wrap, Some(20));
+        assert_eq!(config.complete_view, Some("full.svg".to_owned()));
+        assert_eq!(
+            config.architecture_view,
+            Some("architecture.svg".to_owned())
+        );
+        assert!(!config.no_legend);
+        assert!(config.full_legend);
+        assert_eq!(config.severities, vec![("C01".to_owned(), DiagType::Error)]);
+        assert_eq!(config.output_directory, ".");
+        assert_eq!(config.sibling_order, SiblingOrder::Authored);
+        assert_eq!(config.include_directories, vec!["lib".to_owned()]);
+        Ok(())
+    }
+
+    #[test]
+    fn paths_relative_to_project_file() -> Result<()> {
+        let temp = assert_fs::TempDir::new()?;
+        let path = temp.path().join("project").join("gsn2x.yaml");
+        std::fs::create_dir(temp.path().join("project"))?;
+        std::fs::write(
+            &path,
+            "inputs: [main.gsn.yaml]\ninclude: [../lib]\nbaseline: baseline.jsonl\nstylesheet: [gsn.css, https://example.com/gsn.css]\nfull: full.svg\n",
+        )?;
+        let path = path.to_string_lossy().replace('\\', "/");
+        let dir = path.trim_end_matches("gsn2x.yaml");
+        let project = ProjectFile::read(&path)?;
+        assert_eq!(project.inputs, Some(vec![format!("{dir}main.gsn.yaml")]));
+        assert_eq!(project.include, Some(vec![format!("{dir}../lib")]));
+        assert_eq!(project.baseline, Some(format!("{dir}baseline.jsonl")));
+        assert_eq!(
+            project.stylesheet,
+            Some(vec![
+                format!("{dir}gsn.css"),
+                "https://example.com/gsn.css".to_owned()
+            ])
+        );
+        assert_eq!(project.full, Some("full.svg".to_owned()));
+        temp.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_project_attribute() {
+        let project = serde_saphyr::from_str::<ProjectFile>("layers: [l1]\n");
+        assert!(project.is_err());
+    }
+
+    #[test]
+    fn defaults_without_project_file() -> Result<()> {
+        let matches =
+            build_command_options().try_get_matches_from(vec!["gsn2x", "--statistics"])?;
+        let config = Config::new(&matches, ProjectFile::default())?;
+        assert_eq!(config.inputs, vec!["index.gsn.yaml".to_owned()]);
+        assert!(config.default_inputs);
+        assert_eq!(config.statistics, Some(None));
+        assert_eq!(config.yaml_dump, None);
+        assert_eq!(config.diagnostics_format, "text");
+        assert_eq!(config.rename, None);
+        assert_eq!(config.sibling_order, SiblingOrder::Lexicographic);
+        Ok(())
+    }
+
+    #[test]
+    fn rename_entry() {
+        assert_eq!(
+            parse_rename("G1=G2"),
+            Ok(("G1".to_owned(), "G2".to_owned()))
+        );
+        assert_eq!(
+            parse_rename("G1"),
+            Err("Expected OLD=NEW, but found G1.".to_owned())
+        );
+        assert_eq!(
+            parse_rename("G1="),
+            Err("Expected OLD=NEW, but found G1=.".to_owned())
+        );
+    }
+}
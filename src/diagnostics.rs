@@ -1,19 +1,87 @@
-use std::fmt::Display;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+};
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum DiagType {
     Warning,
     Error,
 }
 
+///
+/// Position in an input file.
+/// Line and column are 1-indexed.
+///
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct SourceLocation {
+    pub file: String,
+    pub line: u64,
+    pub column: u64,
+}
+
+impl Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
 pub struct DiagMsg {
     pub diag_type: DiagType,
     pub module: Option<String>,
+    pub location: Option<SourceLocation>,
+    pub elements: Vec<String>,
     pub msg: String,
 }
 
+impl DiagMsg {
+    ///
+    /// Get the ID of the check (e.g. V01 or C01) that created the message, if any.
+    ///
+    pub fn get_check_id(&self) -> Option<&str> {
+        self.msg.split_once(": ").map(|(id, _)| id).filter(|id| {
+            id.len() == 3
+                && (id.starts_with('V') || id.starts_with('C'))
+                && id[1..].chars().all(|c| c.is_ascii_digit())
+        })
+    }
+
+    ///
+    /// Get a fingerprint of the message that is stable against changes of the message text.
+    ///
+    /// The fingerprint consists of the check ID, the module and the (sorted) element IDs.
+    /// Messages without a check ID use their text instead.
+    ///
+    pub fn get_fingerprint(&self) -> String {
+        let mut elements = self.elements.iter().map(String::as_str).collect::<Vec<_>>();
+        elements.sort_unstable();
+        format!(
+            "{}|{}|{}",
+            self.get_check_id().unwrap_or(&self.msg),
+            self.module.as_deref().unwrap_or_default(),
+            elements.join(",")
+        )
+    }
+
+    ///
+    /// Get the message without the check ID.
+    ///
+    pub fn get_text(&self) -> &str {
+        match self.get_check_id() {
+            Some(id) => &self.msg[id.len() + 2..],
+            None => &self.msg,
+        }
+    }
+}
+
 impl Display for DiagMsg {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Location first, so that editors and CI systems can pick it up.
+        if let Some(location) = &self.location {
+            write!(f, "{location}: ")?;
+        }
         match self.diag_type {
             // DiagType::Error => f.write_str("\x1b[31;1mError:\x1b[0m")?,
             // DiagType::Warning => f.write_str("\x1b[33;1mWarning:\x1b[0m")?,
@@ -27,32 +95,233 @@ impl Display for DiagMsg {
     }
 }
 
+///
+/// A finding recorded in a baseline file.
+/// Only the fingerprint is used for matching, the message is informational.
+///
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BaselineEntry {
+    pub fingerprint: String,
+    pub message: String,
+}
+
+///
+/// Checks whose findings leave elements that cannot be rendered, e.g., elements without a type.
+/// They stop processing even if they are demoted, suppressed or baselined.
+///
+pub const STRUCTURAL_CHECKS: [&str; 5] = ["V01", "V04", "C12", "C13", "C14"];
+
 #[derive(Default)]
 pub struct Diagnostics {
     pub messages: Vec<DiagMsg>,
     pub warnings: usize,
     pub errors: usize,
+    pub suppressed: usize,
+    pub baselined: Vec<DiagMsg>,
+    /// Findings of structural checks that are not counted as errors
+    pub hidden_structural_findings: usize,
+    pub(crate) baseline: BTreeMap<String, usize>,
+    pub(crate) severities: BTreeMap<String, DiagType>,
+    pub deny_warnings: bool,
+    pub(crate) module_suppressions: BTreeMap<String, BTreeSet<String>>,
+    pub(crate) element_suppressions: BTreeMap<String, BTreeSet<String>>,
 }
 
 impl Diagnostics {
+    ///
+    /// Suppress the checks `check_ids` for all messages of module `module`.
+    ///
+    pub fn suppress_for_module(&mut self, module: &str, check_ids: &[String]) {
+        if !check_ids.is_empty() {
+            self.module_suppressions
+                .entry(module.to_owned())
+                .or_default()
+                .extend(check_ids.iter().cloned());
+        }
+    }
+
+    ///
+    /// Suppress the checks `check_ids` for all messages involving element `element`.
+    ///
+    pub fn suppress_for_element(&mut self, element: &str, check_ids: &[String]) {
+        if !check_ids.is_empty() {
+            self.element_suppressions
+                .entry(element.to_owned())
+                .or_default()
+                .extend(check_ids.iter().cloned());
+        }
+    }
+
+    ///
+    /// Set the severity of messages of check `check`.
+    /// `check` is either a check ID (e.g. C01) or a category (V or C).
+    ///
+    pub fn set_severity(&mut self, check: &str, severity: DiagType) {
+        self.severities.insert(check.to_owned(), severity);
+    }
+
+    ///
+    /// Get the severity of a message according to the severity policy.
+    /// The severity of a check ID takes precedence over the one of its category.
+    ///
+    fn get_severity(&self, msg: &DiagMsg) -> DiagType {
+        let severity = msg
+            .get_check_id()
+            .and_then(|id| {
+                self.severities
+                    .get(id)
+                    .or_else(|| self.severities.get(&id[..1]))
+            })
+            .copied()
+            .unwrap_or(msg.diag_type);
+        if self.deny_warnings {
+            DiagType::Error
+        } else {
+            severity
+        }
+    }
+
+    ///
+    /// Set the baseline of known findings.
+    /// Each entry matches at most one message.
+    ///
+    pub fn set_baseline(&mut self, baseline: &[BaselineEntry]) {
+        for entry in baseline {
+            *self
+                .baseline
+                .entry(entry.fingerprint.to_owned())
+                .or_default() += 1;
+        }
+    }
+
+    ///
+    /// Get all findings, i.e. the reported ones and those matched by the baseline.
+    ///
+    pub fn get_baseline_entries(&self) -> Vec<BaselineEntry> {
+        let mut entries = self
+            .messages
+            .iter()
+            .chain(self.baselined.iter())
+            .map(|msg| BaselineEntry {
+                fingerprint: msg.get_fingerprint(),
+                message: msg.to_string(),
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.fingerprint.cmp(&b.fingerprint));
+        entries
+    }
+
+    ///
+    /// A message is suppressed if its check is suppressed for its module or for any involved element.
+    ///
+    fn is_suppressed(&self, msg: &DiagMsg) -> bool {
+        let Some(check_id) = msg.get_check_id() else {
+            return false;
+        };
+        msg.module
+            .as_ref()
+            .and_then(|m| self.module_suppressions.get(m))
+            .is_some_and(|s| s.contains(check_id))
+            || msg.elements.iter().any(|e| {
+                self.element_suppressions
+                    .get(e)
+                    .is_some_and(|s| s.contains(check_id))
+            })
+    }
+
+    ///
+    /// Run `check` and return its error only if the check reported errors that are counted.
+    /// Suppressed, demoted and baselined errors do not stop processing,
+    /// unless they are findings of [STRUCTURAL_CHECKS].
+    ///
+    pub fn check(&mut self, check: impl FnOnce(&mut Self) -> Result<(), ()>) -> Result<(), ()> {
+        let errors = self.errors;
+        let hidden_structural_findings = self.hidden_structural_findings;
+        match check(self) {
+            Err(())
+                if self.errors > errors
+                    || self.hidden_structural_findings > hidden_structural_findings =>
+            {
+                Err(())
+            }
+            _ => Ok(()),
+        }
+    }
+
     pub fn add_error(&mut self, module: Option<&str>, msg: String) {
-        self.add_msg(DiagType::Error, module, msg);
+        self.add_msg(DiagType::Error, module, None, &[], msg);
     }
 
     pub fn add_warning(&mut self, module: Option<&str>, msg: String) {
-        self.add_msg(DiagType::Warning, module, msg);
+        self.add_msg(DiagType::Warning, module, None, &[], msg);
     }
 
-    fn add_msg(&mut self, dtype: DiagType, module: Option<&str>, msg: String) {
-        match dtype {
-            DiagType::Error => self.errors += 1,
-            DiagType::Warning => self.warnings += 1,
-        }
-        let d = DiagMsg {
+    pub fn add_error_at(
+        &mut self,
+        module: Option<&str>,
+        location: Option<&SourceLocation>,
+        elements: &[&str],
+        msg: String,
+    ) {
+        self.add_msg(DiagType::Error, module, location, elements, msg);
+    }
+
+    pub fn add_warning_at(
+        &mut self,
+        module: Option<&str>,
+        location: Option<&SourceLocation>,
+        elements: &[&str],
+        msg: String,
+    ) {
+        self.add_msg(DiagType::Warning, module, location, elements, msg);
+    }
+
+    fn add_msg(
+        &mut self,
+        dtype: DiagType,
+        module: Option<&str>,
+        location: Option<&SourceLocation>,
+        elements: &[&str],
+        msg: String,
+    ) {
+        let mut d = DiagMsg {
             diag_type: dtype,
             module: module.map(|m| m.to_owned()),
+            location: location.cloned(),
+            elements: elements.iter().map(|&e| e.to_owned()).collect(),
             msg,
         };
+        let is_structural = dtype == DiagType::Error
+            && d.get_check_id()
+                .is_some_and(|id| STRUCTURAL_CHECKS.contains(&id));
+        d.diag_type = self.get_severity(&d);
+        let is_baselined = self
+            .baseline
+            .get(&d.get_fingerprint())
+            .is_some_and(|c| *c > 0);
+        // Structural findings stop processing, even if they are not counted as errors.
+        if is_structural
+            && (d.diag_type != DiagType::Error || is_baselined || self.is_suppressed(&d))
+        {
+            self.hidden_structural_findings += 1;
+        }
+        if self.is_suppressed(&d) {
+            self.suppressed += 1;
+            return;
+        }
+        if let Some(count) = self
+            .baseline
+            .get_mut(&d.get_fingerprint())
+            .filter(|c| **c > 0)
+        {
+            *count -= 1;
+            self.baselined.push(d);
+            return;
+        }
+        match d.diag_type {
+            DiagType::Error => self.errors += 1,
+            DiagType::Warning => self.warnings += 1,
+        }
         self.messages.push(d);
     }
 }
@@ -85,4 +354,153 @@ mod test {
             "Warning: msg2".to_owned()
         );
     }
+
+    #[test]
+    fn check_id() {
+        let mut d = Diagnostics::default();
+        d.add_error(None, "C03: Element G1 has unresolved element".to_owned());
+        d.add_warning(None, "Layer l is not used in file.".to_owned());
+        assert_eq!(d.messages[0].get_check_id(), Some("C03"));
+        assert_eq!(
+            d.messages[0].get_text(),
+            "Element G1 has unresolved element"
+        );
+        assert_eq!(d.messages[1].get_check_id(), None);
+        assert_eq!(d.messages[1].get_text(), "Layer l is not used in file.");
+    }
+
+    #[test]
+    fn add_and_print_location() {
+        let mut d = Diagnostics::default();
+        let location = SourceLocation {
+            file: "file.yaml".to_owned(),
+            line: 3,
+            column: 5,
+        };
+        d.add_error_at(
+            Some("module"),
+            Some(&location),
+            &["G1"],
+            "errmsg".to_owned(),
+        );
+        d.add_warning_at(None, Some(&location), &[], "msg".to_owned());
+        assert_eq!(
+            format!("{}", d.messages.first().unwrap()),
+            "file.yaml:3:5: Error: (module) errmsg".to_owned()
+        );
+        assert_eq!(
+            format!("{}", d.messages.get(1).unwrap()),
+            "file.yaml:3:5: Warning: msg".to_owned()
+        );
+        assert_eq!(d.errors, 1);
+        assert_eq!(d.warnings, 1);
+    }
+
+    #[test]
+    fn suppression() {
+        let mut d = Diagnostics::default();
+        d.suppress_for_module("module", &["C08".to_owned()]);
+        d.suppress_for_element("G1", &["V08".to_owned(), "C02".to_owned()]);
+        d.add_warning(Some("module"), "C08: Element G2 is not used.".to_owned());
+        d.add_warning(Some("other"), "C08: Element G3 is not used.".to_owned());
+        d.add_error_at(
+            Some("other"),
+            None,
+            &["G1"],
+            "V08: Element G1 ...".to_owned(),
+        );
+        d.add_error_at(
+            Some("other"),
+            None,
+            &["G2"],
+            "V08: Element G2 ...".to_owned(),
+        );
+        d.add_error_at(Some("module"), None, &["G1"], "C03: Unresolved".to_owned());
+        d.add_warning(Some("module"), "Layer l is not used.".to_owned());
+        assert_eq!(d.suppressed, 2);
+        assert_eq!(d.warnings, 2);
+        assert_eq!(d.errors, 2);
+        assert_eq!(d.messages.len(), 4);
+    }
+
+    #[test]
+    fn baseline() {
+        let mut d = Diagnostics::default();
+        d.add_warning_at(
+            Some("module"),
+            None,
+            &["G3", "G1"],
+            "C01: Old text".to_owned(),
+        );
+        d.add_warning(None, "Layer l is not used.".to_owned());
+        let baseline = d.get_baseline_entries();
+        assert_eq!(baseline[0].fingerprint, "C01|module|G1,G3");
+        assert_eq!(baseline[1].fingerprint, "Layer l is not used.||");
+
+        let mut d = Diagnostics::default();
+        d.set_baseline(&baseline);
+        d.add_warning_at(
+            Some("module"),
+            None,
+            &["G1", "G3"],
+            "C01: New text".to_owned(),
+        );
+        d.add_warning_at(
+            Some("module"),
+            None,
+            &["G1", "G3"],
+            "C01: New text".to_owned(),
+        );
+        d.add_error_at(Some("module"), None, &["G1"], "C03: Unresolved".to_owned());
+        assert_eq!(d.baselined.len(), 1);
+        assert_eq!(d.warnings, 1);
+        assert_eq!(d.errors, 1);
+        assert_eq!(d.get_baseline_entries().len(), 3);
+    }
+
+    #[test]
+    fn severity_policy() {
+        let mut d = Diagnostics::default();
+        d.set_severity("C", DiagType::Warning);
+        d.set_severity("C03", DiagType::Error);
+        d.set_severity("V08", DiagType::Error);
+        d.add_error(None, "C19: Private".to_owned());
+        d.add_error(None, "C03: Unresolved".to_owned());
+        d.add_warning(None, "V08: Type".to_owned());
+        d.add_warning(None, "Layer l is not used.".to_owned());
+        assert_eq!(d.messages[0].diag_type, DiagType::Warning);
+        assert_eq!(d.messages[1].diag_type, DiagType::Error);
+        assert_eq!(d.messages[2].diag_type, DiagType::Error);
+        assert_eq!(d.messages[3].diag_type, DiagType::Warning);
+        assert_eq!(d.errors, 2);
+        assert_eq!(d.warnings, 2);
+    }
+
+    #[test]
+    fn demoted_structural_findings_stop_checks() {
+        let mut d = Diagnostics::default();
+        d.set_severity("V", DiagType::Warning);
+        let failing = |d: &mut Diagnostics, msg: &str| {
+            d.add_error(None, msg.to_owned());
+            Err(())
+        };
+        assert!(d.check(|d| failing(d, "V02: Undeveloped")).is_ok());
+        assert!(d.check(|d| failing(d, "V01: Unknown type")).is_err());
+        assert_eq!(d.errors, 0);
+        assert_eq!(d.warnings, 2);
+        assert_eq!(d.hidden_structural_findings, 1);
+    }
+
+    #[test]
+    fn deny_warnings() {
+        let mut d = Diagnostics {
+            deny_warnings: true,
+            ..Default::default()
+        };
+        d.set_severity("C01", DiagType::Warning);
+        d.add_warning(None, "C01: Unreferenced".to_owned());
+        d.add_warning(None, "Layer l is not used.".to_owned());
+        assert_eq!(d.errors, 2);
+        assert_eq!(d.warnings, 0);
+    }
 }
@@ -6,6 +6,9 @@ use std::fmt::Debug;
 pub enum EdgeDecorator {
     Acps(Vec<String>),
     Defeated,
+    Multiplicity(String),
+    Optional,
+    Choice(String),
 }
 
 ///
@@ -61,7 +64,17 @@ where
     edges: &'a BTreeMap<String, Vec<(String, EdgeType)>>,
     root_nodes: Vec<&'a str>,
     parent_edges: BTreeMap<&'a str, Vec<(&'a str, EdgeType)>>,
-    edge_decorators: BTreeMap<(String, String), EdgeDecorator>,
+    edge_decorators: BTreeMap<(String, String), Vec<EdgeDecorator>>,
+}
+
+///
+/// A strongly connected component of a graph with its elementary cycles.
+///
+pub(super) struct CyclicComponent<'a> {
+    pub elements: Vec<&'a str>,
+    pub cycles: Vec<Vec<&'a str>>,
+    /// There are more cycles than listed in `cycles`.
+    pub truncated: bool,
 }
 
 impl<'a, NodeType, EdgeType> DirectedGraph<'a, NodeType, EdgeType>
@@ -92,13 +105,14 @@ where
 
     ///
     /// Add edge decorators.
-    /// Edge decorators are required to implement Assurance Claim Points.
-    /// A decorator is basically a string (better list of strings).
+    /// Edge decorators are required to implement Assurance Claim Points,
+    /// defeated relations and the annotations of argument patterns.
+    /// An edge can have multiple decorators.
     ///
     ///
     pub fn add_edge_decorators(
         &mut self,
-        edge_decorators: BTreeMap<(String, String), EdgeDecorator>,
+        edge_decorators: BTreeMap<(String, String), Vec<EdgeDecorator>>,
     ) {
         self.edge_decorators = edge_decorators;
     }
@@ -107,9 +121,11 @@ where
     /// Get the edge decorators.
     ///
     ///
-    pub fn get_edge_decorator(&self, source: &str, target: &str) -> Option<&EdgeDecorator> {
+    pub fn get_edge_decorators(&self, source: &str, target: &str) -> &[EdgeDecorator] {
         self.edge_decorators
             .get(&(source.to_owned(), target.to_owned()))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
     }
 
     ///
@@ -179,48 +195,146 @@ where
     }
 
     ///
-    /// Get the first cycle in the graph.
-    /// If no cycle is found None is returned.
+    /// Get the elementary cycles in the graph grouped by strongly connected components.
     ///
-    pub fn get_first_cycle(&'a self) -> Option<(&'a str, Vec<&'a str>)> {
-        let mut stack = self.root_nodes.iter().map(|&n| (n, 0)).collect::<Vec<_>>();
-        let mut ancestors = Vec::new();
-        let mut depth = 0;
-        'cycle_found: {
-            while let Some((p_id, rdepth)) = stack.pop() {
-                // Jump back to current ancestor
-                if rdepth < depth {
-                    ancestors.truncate(rdepth);
-                    depth = rdepth;
+    /// Each cycle starts and ends with its smallest element and is listed once.
+    /// At most `limit` cycles are listed per component. If there are more, the component is marked as truncated.
+    /// Only edges to real children are considered.
+    ///
+    pub fn get_cycles(&'a self, limit: usize) -> Vec<CyclicComponent<'a>> {
+        self.get_cyclic_components()
+            .into_iter()
+            .map(|elements| {
+                let mut cycles = vec![];
+                for &start in &elements {
+                    self.add_cycles_from(start, &elements, limit + 1 - cycles.len(), &mut cycles);
+                    if cycles.len() > limit {
+                        break;
+                    }
                 }
-                // Remember the current node if it has no other real children
-                if self
-                    .get_real_children(p_id)
-                    .iter()
-                    .filter(|&&x| !self.get_real_children(x).is_empty())
-                    .count()
-                    > 0
-                {
-                    depth += 1;
-                    ancestors.push(p_id);
+                let truncated = cycles.len() > limit;
+                cycles.truncate(limit);
+                CyclicComponent {
+                    elements,
+                    cycles,
+                    truncated,
+                }
+            })
+            .collect()
+    }
+
+    ///
+    /// Add at most `limit` elementary cycles through `start` to `cycles`.
+    /// Only elements of `component` that are greater than `start` are used,
+    /// thus each cycle is found from its smallest element only.
+    ///
+    fn add_cycles_from(
+        &'a self,
+        start: &'a str,
+        component: &[&'a str],
+        limit: usize,
+        cycles: &mut Vec<Vec<&'a str>>,
+    ) {
+        let candidates: BTreeSet<&str> = component.iter().copied().filter(|&n| n > start).collect();
+        // Only elements from which `start` can be reached are worth visiting.
+        let mut reaching: BTreeSet<&str> = BTreeSet::new();
+        let mut pending = vec![start];
+        while let Some(node) = pending.pop() {
+            for &parent in &candidates {
+                if !reaching.contains(parent) && self.get_real_children(parent).contains(&node) {
+                    reaching.insert(parent);
+                    pending.push(parent);
                 }
-                for &child_node in self.get_real_children(p_id).iter() {
-                    if !self.get_real_children(child_node).is_empty() {
-                        if ancestors.contains(&child_node) {
-                            let mut reported_ancestors = Vec::from(
-                                ancestors.rsplit(|&x| x == child_node).next().unwrap(), // unwrap is ok, since it is checked above that `ancestors` contains `child_node`
-                            );
-                            // Add nodes for reporting the found cycle
-                            reported_ancestors.insert(0, child_node);
-                            reported_ancestors.push(child_node);
-                            break 'cycle_found Some((p_id, reported_ancestors));
+            }
+        }
+        let found = cycles.len();
+        // The current path and the position of the next child of each element on it
+        let mut path = vec![(start, 0)];
+        while let Some(&(node, position)) = path.last() {
+            let children = self.get_real_children(node);
+            let Some(&child) = children.get(position) else {
+                path.pop();
+                continue;
+            };
+            // unwrap is ok, since `path` is not empty
+            path.last_mut().unwrap().1 += 1;
+            if child == start {
+                let mut cycle = path.iter().map(|&(n, _)| n).collect::<Vec<_>>();
+                cycle.push(start);
+                cycles.push(cycle);
+                if cycles.len() - found >= limit {
+                    return;
+                }
+            } else if reaching.contains(child) && !path.iter().any(|&(n, _)| n == child) {
+                path.push((child, 0));
+            }
+        }
+    }
+
+    ///
+    /// Get the strongly connected components of the graph that contain a cycle.
+    /// The components and their elements are sorted.
+    ///
+    /// This is Tarjan's algorithm without recursion.
+    ///
+    fn get_cyclic_components(&'a self) -> Vec<Vec<&'a str>> {
+        let mut indices: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut low_links: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut stack: Vec<&str> = vec![];
+        let mut on_stack: BTreeSet<&str> = BTreeSet::new();
+        let mut components = vec![];
+        for start in self.nodes.keys().map(String::as_str) {
+            if indices.contains_key(start) {
+                continue;
+            }
+            // Nodes currently visited and the position of their next child
+            let mut visits = vec![(start, 0)];
+            indices.insert(start, indices.len());
+            low_links.insert(start, indices[start]);
+            stack.push(start);
+            on_stack.insert(start);
+            while let Some(&(node, position)) = visits.last() {
+                let children = self.get_real_children(node);
+                if let Some(&child) = children.get(position) {
+                    // unwrap is ok, since `visits` is not empty
+                    visits.last_mut().unwrap().1 += 1;
+                    if !self.nodes.contains_key(child) {
+                        // Unresolved references are reported elsewhere.
+                    } else if !indices.contains_key(child) {
+                        indices.insert(child, indices.len());
+                        low_links.insert(child, indices[child]);
+                        stack.push(child);
+                        on_stack.insert(child);
+                        visits.push((child, 0));
+                    } else if on_stack.contains(child) {
+                        let low_link = min(low_links[node], indices[child]);
+                        low_links.insert(node, low_link);
+                    }
+                } else {
+                    visits.pop();
+                    if let Some(&(parent, _)) = visits.last() {
+                        let low_link = min(low_links[parent], low_links[node]);
+                        low_links.insert(parent, low_link);
+                    }
+                    if low_links[node] == indices[node] {
+                        let mut component = vec![];
+                        while let Some(member) = stack.pop() {
+                            on_stack.remove(member);
+                            component.push(member);
+                            if member == node {
+                                break;
+                            }
+                        }
+                        if component.len() > 1 || children.contains(&node) {
+                            component.sort();
+                            components.push(component);
                         }
-                        stack.push((child_node, depth));
                     }
                 }
             }
-            None
         }
+        components.sort();
+        components
     }
 
     ///
@@ -46,7 +46,7 @@ impl<'a> DirGraph<'a> {
         mut nodes: BTreeMap<String, SvgNode>,
         edges: BTreeMap<String, Vec<(String, EdgeType)>>,
         mut output: impl std::io::Write,
-        edge_decorators: BTreeMap<(String, String), EdgeDecorator>,
+        edge_decorators: BTreeMap<(String, String), Vec<EdgeDecorator>>,
     ) -> Result<(), std::io::Error> {
         // Calculate node sizes
         nodes.values_mut().for_each(|n| n.calculate_size());
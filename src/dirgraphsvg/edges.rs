@@ -19,7 +19,10 @@ use super::{
     DirGraph,
     layout::Margin,
     nodes::{Port, SvgNode},
-    render::{ACP_BOX_SIZE, BOTTOM_LEFT_CORNER, TOP_RIGHT_CORNER, create_text},
+    render::{
+        ACP_BOX_SIZE, BOTTOM_LEFT_CORNER, CHOICE_DIAMOND_SIZE, PATTERN_BALL_RADIUS,
+        TOP_RIGHT_CORNER, create_text,
+    },
     util::{font::str_line_bounding_box, point2d::Point2D},
 };
 
@@ -102,6 +105,8 @@ impl<'a> From<GsnEdgeType<'a>> for EdgeType<'a> {
             GsnEdgeType::InContextOf => Self::OneWay(SingleEdge::InContextOf),
             GsnEdgeType::ChallengesNode => Self::OneWay(SingleEdge::ChallengesNode),
             GsnEdgeType::ChallengesRelation(r) => Self::OneWay(SingleEdge::ChallengesRelation(r)),
+            GsnEdgeType::SupportedByPattern(_) => Self::OneWay(SingleEdge::SupportedBy),
+            GsnEdgeType::InContextOfPattern(_) => Self::OneWay(SingleEdge::InContextOf),
         }
     }
 }
@@ -232,17 +237,36 @@ pub(super) fn render_edge(
     }
     e = e.set("class", classes);
     let mut result: Vec<Element> = vec![e.into()];
-    if let Some(EdgeDecorator::Defeated) = graph.get_edge_decorator(source, &target.0) {
-        let p = get_mid_point(&curve_points);
-        result.push(
-            Use::new()
-                .set("href", "#defeated_cross")
-                .set("x", p.x - 10)
-                .set("y", p.y - 10)
-                .into(),
-        );
+    for decorator in graph.get_edge_decorators(source, &target.0) {
+        match decorator {
+            EdgeDecorator::Defeated => {
+                let p = get_mid_point(&curve_points);
+                result.push(
+                    Use::new()
+                        .set("href", "#defeated_cross")
+                        .set("x", p.x - 10)
+                        .set("y", p.y - 10)
+                        .into(),
+                );
+            }
+            EdgeDecorator::Acps(acps) => {
+                result.push(render_acps(source, target, acps, &curve_points));
+            }
+            EdgeDecorator::Multiplicity(label) => {
+                result.push(render_multiplicity(
+                    "#multiplicity_ball",
+                    label,
+                    &curve_points,
+                ));
+            }
+            EdgeDecorator::Optional => {
+                result.push(render_multiplicity("#optional_ball", "", &curve_points));
+            }
+            EdgeDecorator::Choice(label) => {
+                result.push(render_choice(label, &curve_points));
+            }
+        }
     }
-    result.extend(render_acps(graph, source, target, curve_points));
     result
 }
 
@@ -329,53 +353,116 @@ fn create_curve_points(
 ///
 ///
 fn render_acps(
-    graph: &DirectedGraph<'_, RefCell<SvgNode>, EdgeType>,
     source: &str,
     target: &(String, EdgeType),
-    curve_points: Vec<(Point2D<i32>, Point2D<i32>)>,
-) -> Option<Element> {
-    if let Some(EdgeDecorator::Acps(acps)) = graph.get_edge_decorator(source, &target.0) {
-        let mut svg_acp = Group::new()
-            .set(
-                "id",
-                format!(
-                    "acp_{}_{}_{}",
-                    escape_text(source).to_lowercase(),
-                    escape_text(&target.0).to_lowercase(),
-                    escape_text(&acps.join("_")).to_lowercase()
-                ),
-            )
-            .set("class", "gsnacp");
-        let center_segment = (curve_points.len() - 1) / 2;
-        let curve = CubicBezierCurve::new(
-            curve_points[center_segment].0,
-            curve_points[center_segment].1,
-            curve_points[center_segment + 1].1,
-            curve_points[center_segment + 1].0,
+    acps: &[String],
+    curve_points: &[(Point2D<i32>, Point2D<i32>)],
+) -> Element {
+    let mut svg_acp = Group::new()
+        .set(
+            "id",
+            format!(
+                "acp_{}_{}_{}",
+                escape_text(source).to_lowercase(),
+                escape_text(&target.0).to_lowercase(),
+                escape_text(&acps.join("_")).to_lowercase()
+            ),
+        )
+        .set("class", "gsnacp");
+    let center_segment = (curve_points.len() - 1) / 2;
+    let curve = CubicBezierCurve::new(
+        curve_points[center_segment].0,
+        curve_points[center_segment].1,
+        curve_points[center_segment + 1].1,
+        curve_points[center_segment + 1].0,
+    );
+    let coords = curve.get_coordinates_for_t(0.5);
+    let turning_vector = curve.get_first_derivative_for_t(0.5).normalize();
+    let acp_text = acps.join(", ");
+    let acp_x = coords.x - ACP_BOX_SIZE;
+    let acp_y = coords.y - ACP_BOX_SIZE;
+    let acp_text_bb = str_line_bounding_box(&acp_text, false);
+    let acp_x_text = coords.x
+        + ((ACP_BOX_SIZE + PADDING_HORIZONTAL) as f64 * turning_vector.y) as i32
+        - ((1.0 - turning_vector.y) * ((acp_text_bb.0) as f64 / 2.0)) as i32;
+    let acp_y_text = coords.y
+        + ((acp_text_bb.1 + PADDING_VERTICAL) as f64 * turning_vector.x) as i32
+        + ((1.0 - turning_vector.x) * ACP_BOX_SIZE as f64) as i32;
+    svg_acp.append(
+        Use::new()
+            .set("href", "#acp")
+            .set("x", acp_x)
+            .set("y", acp_y),
+    );
+    svg_acp.append(create_text(&acp_text.into(), acp_x_text, acp_y_text, false));
+    svg_acp.into()
+}
+
+///
+/// Render the multiplicity or optionality ball of an argument pattern.
+/// The ball is placed at the end of the edge right before the arrow.
+///
+///
+fn render_multiplicity(
+    symbol: &str,
+    label: &str,
+    curve_points: &[(Point2D<i32>, Point2D<i32>)],
+) -> Element {
+    // unwrap is ok, since there are always a start and an end point
+    let (end, end_sup) = curve_points.last().unwrap();
+    let direction = (*end_sup - *end).normalize();
+    let center = *end
+        + (
+            (direction.x * PATTERN_BALL_RADIUS as f64) as i32,
+            (direction.y * PATTERN_BALL_RADIUS as f64) as i32,
         );
-        let coords = curve.get_coordinates_for_t(0.5);
-        let turning_vector = curve.get_first_derivative_for_t(0.5).normalize();
-        let acp_text = acps.join(", ");
-        let acp_x = coords.x - ACP_BOX_SIZE;
-        let acp_y = coords.y - ACP_BOX_SIZE;
-        let acp_text_bb = str_line_bounding_box(&acp_text, false);
-        let acp_x_text = coords.x
-            + ((ACP_BOX_SIZE + PADDING_HORIZONTAL) as f64 * turning_vector.y) as i32
-            - ((1.0 - turning_vector.y) * ((acp_text_bb.0) as f64 / 2.0)) as i32;
-        let acp_y_text = coords.y
-            + ((acp_text_bb.1 + PADDING_VERTICAL) as f64 * turning_vector.x) as i32
-            + ((1.0 - turning_vector.x) * ACP_BOX_SIZE as f64) as i32;
-        svg_acp.append(
-            Use::new()
-                .set("href", "#acp")
-                .set("x", acp_x)
-                .set("y", acp_y),
+    let mut svg_multiplicity = Group::new().set("class", "gsnmultiplicity");
+    svg_multiplicity.append(
+        Use::new()
+            .set("href", symbol)
+            .set("x", center.x - PATTERN_BALL_RADIUS)
+            .set("y", center.y - PATTERN_BALL_RADIUS),
+    );
+    if !label.is_empty() {
+        svg_multiplicity.append(create_text(
+            &label.into(),
+            center.x + PATTERN_BALL_RADIUS + PADDING_HORIZONTAL,
+            center.y + PATTERN_BALL_RADIUS,
+            false,
+        ));
+    }
+    svg_multiplicity.into()
+}
+
+///
+/// Render the choice diamond of an argument pattern.
+/// The diamond is placed at the start of the edge.
+///
+///
+fn render_choice(label: &str, curve_points: &[(Point2D<i32>, Point2D<i32>)]) -> Element {
+    let (start, start_sup) = curve_points[0];
+    let direction = (start_sup - start).normalize();
+    let center = start
+        + (
+            (direction.x * CHOICE_DIAMOND_SIZE as f64) as i32,
+            (direction.y * CHOICE_DIAMOND_SIZE as f64) as i32,
         );
-        svg_acp.append(create_text(&acp_text.into(), acp_x_text, acp_y_text, false));
-        Some(svg_acp.into())
-    } else {
-        None
+    let mut svg_choice = Group::new().set("class", "gsnchoice");
+    svg_choice.append(
+        Use::new()
+            .set("href", "#choice_diamond")
+            .set("x", center.x - CHOICE_DIAMOND_SIZE)
+            .set("y", center.y - CHOICE_DIAMOND_SIZE),
+    );
+    if !label.is_empty() {
+        svg_choice.append(create_text(
+            &label.into(),
+            center.x + CHOICE_DIAMOND_SIZE + PADDING_HORIZONTAL,
+            center.y + CHOICE_DIAMOND_SIZE,
+            false,
+        ));
     }
+    svg_choice.into()
 }
 
 ///
@@ -2,7 +2,7 @@ use std::cell::RefCell;
 
 use svg::{
     Node,
-    node::element::{Anchor, Element, Line, Use},
+    node::element::{Anchor, Element, Line, Path, Use, path::Data},
 };
 
 use crate::{
@@ -65,6 +65,8 @@ pub struct SvgNode {
     text: MarkdownText,
     masked: bool,
     defeated: bool,
+    uninstantiated: bool,
+    public: bool,
     url: Option<String>,
     classes: Vec<String>,
     rank_increment: Option<usize>,
@@ -98,6 +100,10 @@ struct SizeContext {
 }
 
 const DEFEATED_OFFSET: i32 = 5;
+const UNINSTANTIATED_TRIANGLE: i32 = 6;
+const PUBLIC_INDICATOR_WIDTH: i32 = 12;
+const PUBLIC_INDICATOR_HEIGHT: i32 = 8;
+const PUBLIC_INDICATOR_TAB: i32 = 5;
 const OFFSET_IDENTIFIER: i32 = 5;
 const MODULE_TAB_HEIGHT: i32 = 10;
 
@@ -137,7 +143,7 @@ impl SvgNode {
                 Port::West => self.y,
             },
         };
-        if let NodeType::Box(BoxType::Module) = &self.node_type
+        if let NodeType::Box(BoxType::Module | BoxType::Contract) = &self.node_type
             && port == super::Port::North
         {
             coords.y += MODULE_TAB_HEIGHT;
@@ -230,6 +236,13 @@ impl SvgNode {
 
         render_acp_box(self, &mut g);
 
+        // Render triangle if uninstantiated.
+        // Undeveloped boxes render the combined symbol themselves.
+        if self.uninstantiated && !matches!(&self.node_type, NodeType::Box(BoxType::Undeveloped(_)))
+        {
+            render_uninstantiated(self, &mut g, border_color);
+        }
+
         // Render cross if defeated.
         if self.defeated {
             render_defeated(self, &mut g);
@@ -264,6 +277,12 @@ impl SvgNode {
         if gsn_node.defeated {
             classes.push("gsndefeated".to_owned());
         }
+        if gsn_node.uninstantiated {
+            classes.push("gsn_uninstantiated".to_owned());
+        }
+        if gsn_node.public {
+            classes.push("gsn_public".to_owned());
+        }
         classes.append(
             &mut add_classes
                 .iter()
@@ -292,6 +311,8 @@ impl SvgNode {
             height: 0,
             masked,
             defeated: gsn_node.defeated,
+            uninstantiated: gsn_node.uninstantiated,
+            public: gsn_node.public,
             identifier: identifier.to_owned(),
             text: node_text.into(),
             url: module_url,
@@ -317,6 +338,8 @@ impl SvgNode {
             text: MarkdownText::from(""),
             masked: false,
             defeated: false,
+            uninstantiated: false,
+            public: false,
             url: None,
             classes: vec![],
             rank_increment: None,
@@ -734,6 +757,30 @@ impl SvgNode {
         n.node_type = NodeType::Box(BoxType::Module);
         n
     }
+
+    ///
+    /// New Contract Module for Architecture View.
+    ///
+    pub fn new_contract_module(
+        identifier: &str,
+        gsn_node: &GsnNode,
+        masked: bool,
+        layers: &[String],
+        module_url: Option<String>,
+        char_wrap: Option<u32>,
+    ) -> Self {
+        let mut n = SvgNode::new(
+            identifier,
+            gsn_node,
+            masked,
+            layers,
+            module_url,
+            &["gsnmodule", "gsncontract"],
+            char_wrap,
+        );
+        n.node_type = NodeType::Box(BoxType::Contract);
+        n
+    }
 }
 
 ///
@@ -759,6 +806,44 @@ fn render_acp_box(node: &SvgNode, context: &mut Element) {
     }
 }
 
+///
+/// Render triangle below an uninstantiated element (Argument Pattern Extension).
+///
+fn render_uninstantiated(node: &SvgNode, context: &mut Element, border_color: &str) {
+    let data = Data::new()
+        .move_to((node.x, node.y + node.height / 2))
+        .line_by((UNINSTANTIATED_TRIANGLE, UNINSTANTIATED_TRIANGLE * 2))
+        .horizontal_line_by(-UNINSTANTIATED_TRIANGLE * 2)
+        .close();
+    let uninstantiated_triangle = Path::new()
+        .set("fill-opacity", "0")
+        .set("stroke", border_color)
+        .set("stroke-width", 1u32)
+        .set("d", data);
+    context.append(uninstantiated_triangle);
+}
+
+///
+/// Render the public indicator with its upper right corner at `x`, `y` (Modular Extension).
+///
+fn render_public_indicator(x: i32, y: i32, context: &mut Element, border_color: &str) {
+    let data = Data::new()
+        .move_to((x - PUBLIC_INDICATOR_WIDTH, y))
+        .horizontal_line_by(PUBLIC_INDICATOR_TAB)
+        .vertical_line_by(PUBLIC_INDICATOR_HEIGHT / 4)
+        .line_to((x, y + PUBLIC_INDICATOR_HEIGHT / 4))
+        .line_to((x, y + PUBLIC_INDICATOR_HEIGHT))
+        .horizontal_line_by(-PUBLIC_INDICATOR_WIDTH)
+        .close();
+    let public_indicator = Path::new()
+        .set("fill-opacity", "0")
+        .set("stroke", border_color)
+        .set("stroke-width", 1u32)
+        .set("d", data)
+        .set("class", "public");
+    context.append(public_indicator);
+}
+
 ///
 /// Render cross over defeated element.
 ///
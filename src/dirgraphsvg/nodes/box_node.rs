@@ -1,7 +1,7 @@
 use svg::node::element::{Element, Path, Title, path::Data};
 
 use crate::dirgraphsvg::{
-    nodes::{OFFSET_IDENTIFIER, render_text},
+    nodes::{OFFSET_IDENTIFIER, render_public_indicator, render_text},
     render::{PADDING_HORIZONTAL, PADDING_VERTICAL, create_text},
     util::font::str_line_bounding_box,
 };
@@ -11,11 +11,14 @@ use super::{SizeContext, SvgNode};
 const MODULE_TAB_HEIGHT: i32 = 10;
 const UNDEVELOPED_DIAMOND: i32 = 5;
 const CONTEXT_BUMP: i32 = 10;
+const PUBLIC_INDICATOR_OFFSET: i32 = 3;
+const CONTRACT_BAR_HEIGHT: i32 = 6;
 
 pub(crate) enum BoxType {
     Normal(i32),
     Undeveloped(i32),
     Module,
+    Contract,
     Context,
 }
 
@@ -52,6 +55,9 @@ impl BoxType {
             BoxType::Module => {
                 height += MODULE_TAB_HEIGHT;
             }
+            BoxType::Contract => {
+                height += MODULE_TAB_HEIGHT + CONTRACT_BAR_HEIGHT * 2;
+            }
             BoxType::Context => {
                 width += CONTEXT_BUMP * 2;
             }
@@ -75,7 +81,7 @@ impl BoxType {
                 .line_to((node.x + node.width / 2 - skew / 2, node.y + node.height / 2))
                 .line_to((node.x - node.width / 2 - skew / 2, node.y + node.height / 2))
                 .close(),
-            BoxType::Module => Data::new()
+            BoxType::Module | BoxType::Contract => Data::new()
                 .move_to((node.x - node.width / 2, node.y - node.height / 2))
                 .horizontal_line_by(30)
                 .vertical_line_by(MODULE_TAB_HEIGHT)
@@ -131,8 +137,10 @@ impl BoxType {
             x += CONTEXT_BUMP;
         }
         let mut y = node.y - node.height / 2 + PADDING_VERTICAL;
-        if let BoxType::Module = self {
-            y += MODULE_TAB_HEIGHT;
+        match self {
+            BoxType::Module => y += MODULE_TAB_HEIGHT,
+            BoxType::Contract => y += MODULE_TAB_HEIGHT + CONTRACT_BAR_HEIGHT,
+            _ => (),
         }
         y += str_line_bounding_box("", false).1;
         context.append(create_text(&(&node.identifier).into(), x, y, true));
@@ -142,6 +150,38 @@ impl BoxType {
             render_text(&node.text, context, Some((skew, node.height)), x, y);
         }
 
+        if node.public {
+            let right = match &self {
+                BoxType::Normal(skew) | BoxType::Undeveloped(skew) => {
+                    node.x + node.width / 2 + skew / 2 - PADDING_HORIZONTAL
+                }
+                BoxType::Context => node.x + node.width / 2 - CONTEXT_BUMP,
+                BoxType::Module | BoxType::Contract => node.x + node.width / 2 - PADDING_HORIZONTAL,
+            };
+            let top = node.y - node.height / 2 + PUBLIC_INDICATOR_OFFSET;
+            render_public_indicator(right, top, context, border_color);
+        }
+
+        if let BoxType::Contract = self {
+            // Bars below the tab and above the bottom of the module symbol
+            let data = Data::new()
+                .move_to((
+                    node.x - node.width / 2,
+                    node.y - node.height / 2 + MODULE_TAB_HEIGHT + CONTRACT_BAR_HEIGHT,
+                ))
+                .horizontal_line_by(node.width)
+                .move_to((
+                    node.x - node.width / 2,
+                    node.y + node.height / 2 - CONTRACT_BAR_HEIGHT,
+                ))
+                .horizontal_line_by(node.width);
+            let contract_bars = Path::new()
+                .set("stroke", border_color)
+                .set("stroke-width", 1u32)
+                .set("d", data);
+            context.append(contract_bars);
+        }
+
         if let BoxType::Undeveloped(_) = self {
             let data = Data::new()
                 .move_to((node.x, node.y + node.height / 2))
@@ -155,6 +195,20 @@ impl BoxType {
                 .set("stroke-width", 1u32)
                 .set("d", data);
             context.append(undeveloped_diamond);
+            if node.uninstantiated {
+                // To be developed and instantiated
+                let data = Data::new()
+                    .move_to((
+                        node.x - UNDEVELOPED_DIAMOND,
+                        node.y + node.height / 2 + UNDEVELOPED_DIAMOND,
+                    ))
+                    .horizontal_line_by(UNDEVELOPED_DIAMOND * 2);
+                let instantiation_line = Path::new()
+                    .set("stroke", border_color)
+                    .set("stroke-width", 1u32)
+                    .set("d", data);
+                context.append(instantiation_line);
+            }
         }
     }
 }
@@ -1,7 +1,7 @@
 use svg::node::element::{Element, Path, Title, path::Data};
 
 use crate::dirgraphsvg::{
-    nodes::{OFFSET_IDENTIFIER, render_text},
+    nodes::{OFFSET_IDENTIFIER, render_public_indicator, render_text},
     render::{PADDING_HORIZONTAL, create_text},
 };
 
@@ -111,5 +111,14 @@ impl EllipticalType {
                 true,
             ));
         }
+
+        if node.public {
+            render_public_indicator(
+                node.x + node.width / 2,
+                node.y - node.height / 2,
+                context,
+                border_color,
+            );
+        }
     }
 }
@@ -5,8 +5,8 @@ use anyhow::Context;
 use svg::{
     Document, Node,
     node::element::{
-        Anchor, Definitions, Element, Group, Marker, Path, Polyline, Rectangle, Style, Symbol,
-        TSpan, Text, Title, path::Data,
+        Anchor, Circle, Definitions, Element, Group, Marker, Path, Polyline, Rectangle, Style,
+        Symbol, TSpan, Text, Title, path::Data,
     },
 };
 
@@ -27,6 +27,8 @@ use super::{
 };
 
 pub const ACP_BOX_SIZE: i32 = 5;
+pub const PATTERN_BALL_RADIUS: i32 = 4;
+pub const CHOICE_DIAMOND_SIZE: i32 = 6;
 pub const PADDING_VERTICAL: i32 = 7;
 pub const PADDING_HORIZONTAL: i32 = 7;
 
@@ -77,12 +79,17 @@ pub(super) fn render_graph(
             ms.module = true;
         }
     }
-    // Add ACP marker if any edge has ACP decorator
+    // Add markers if any edge has the respective decorator
     for (source, targets) in graph.get_edges() {
         for (target, _) in targets {
-            if let Some(EdgeDecorator::Acps(_)) = graph.get_edge_decorator(source, target) {
-                ms.acp = true;
-                break;
+            for decorator in graph.get_edge_decorators(source, target) {
+                match decorator {
+                    EdgeDecorator::Acps(_) => ms.acp = true,
+                    EdgeDecorator::Defeated => (),
+                    EdgeDecorator::Multiplicity(_) => ms.multiplicity = true,
+                    EdgeDecorator::Optional => ms.optional = true,
+                    EdgeDecorator::Choice(_) => ms.choice = true,
+                }
             }
         }
     }
@@ -250,6 +257,9 @@ pub struct MarkersSymbols {
     pub module: bool,
     pub acp: bool,
     pub defeated: bool,
+    pub multiplicity: bool,
+    pub optional: bool,
+    pub choice: bool,
 }
 
 ///
@@ -386,6 +396,60 @@ fn setup_basics(mut document: Document, ms: MarkersSymbols) -> Document {
         defs.append(defeated_cross_marker);
     }
 
+    if ms.multiplicity {
+        let multiplicity_ball = Circle::new()
+            .set("cx", PATTERN_BALL_RADIUS)
+            .set("cy", PATTERN_BALL_RADIUS)
+            .set("r", PATTERN_BALL_RADIUS)
+            .set("stroke", "black")
+            .set("stroke-width", 1u32)
+            .set("fill", "black");
+        let multiplicity_image = Symbol::new()
+            .set("id", "multiplicity_ball")
+            .set("width", PATTERN_BALL_RADIUS * 2)
+            .set("height", PATTERN_BALL_RADIUS * 2)
+            .set("overflow", "visible")
+            .add(multiplicity_ball);
+        defs.append(multiplicity_image);
+    }
+    if ms.optional {
+        let optional_ball = Circle::new()
+            .set("cx", PATTERN_BALL_RADIUS)
+            .set("cy", PATTERN_BALL_RADIUS)
+            .set("r", PATTERN_BALL_RADIUS)
+            .set("stroke", "black")
+            .set("stroke-width", 1u32)
+            .set("fill", "white");
+        let optional_image = Symbol::new()
+            .set("id", "optional_ball")
+            .set("width", PATTERN_BALL_RADIUS * 2)
+            .set("height", PATTERN_BALL_RADIUS * 2)
+            .set("overflow", "visible")
+            .add(optional_ball);
+        defs.append(optional_image);
+    }
+    if ms.choice {
+        let choice_polyline = Polyline::new()
+            .set(
+                "points",
+                format!(
+                    "{s} 0, {d} {s}, {s} {d}, 0 {s}, {s} 0",
+                    s = CHOICE_DIAMOND_SIZE,
+                    d = CHOICE_DIAMOND_SIZE * 2
+                ),
+            )
+            .set("stroke", "black")
+            .set("stroke-width", 1u32)
+            .set("fill", "white");
+        let choice_image = Symbol::new()
+            .set("id", "choice_diamond")
+            .set("width", CHOICE_DIAMOND_SIZE * 2)
+            .set("height", CHOICE_DIAMOND_SIZE * 2)
+            .set("overflow", "visible")
+            .add(choice_polyline);
+        defs.append(choice_image);
+    }
+
     document = document.add(defs).set("class", "gsndiagram");
     document
 }
@@ -86,6 +86,14 @@ pub fn get_filename(path: &str) -> Option<&str> {
         .filter(|&filename| !(filename.is_empty() || filename == ".." || filename == "."))
 }
 
+///
+/// Is `path` a JSON input file?
+/// All other input files are YAML.
+///
+pub fn is_json_file(path: &str) -> bool {
+    Path::new(path).extension().is_some_and(|e| e == "json")
+}
+
 ///
 /// Prefix `input_filename` with `output_path`.
 ///
@@ -170,6 +178,13 @@ mod test {
         assert_eq!(get_filename("a.b"), Some("a.b"));
     }
 
+    #[test]
+    fn json_file() {
+        assert!(is_json_file("dir/index.gsn.json"));
+        assert!(!is_json_file("index.gsn.yaml"));
+        assert!(!is_json_file("json"));
+    }
+
     #[test]
     fn relative_path() -> Result<()> {
         let rel = get_relative_path("./Cargo.toml", "examples/modular/index.gsn.yaml");
@@ -1,5 +1,5 @@
 use crate::{
-    diagnostics::Diagnostics,
+    diagnostics::{Diagnostics, SourceLocation},
     dirgraph::{DirectedGraphEdgeType, DirectedGraphNodeType},
     dirgraphsvg::edges::{EdgeType, SingleEdge},
 };
@@ -11,14 +11,23 @@ use serde::{
 };
 use serde_json::Value;
 
+use location::ElementLocation;
 use std::ops::Not;
 use std::{
+    cmp::Ordering,
     collections::{BTreeMap, BTreeSet},
     fmt::{self, Display},
     marker::PhantomData,
     path::{Path, PathBuf},
 };
 pub mod check;
+pub mod edit;
+pub mod fix;
+pub mod format;
+pub mod instantiation;
+pub mod location;
+pub mod rename;
+pub mod schema;
 pub mod validation;
 
 #[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
@@ -31,6 +40,10 @@ pub enum GsnNodeType {
     Assumption,
     CounterGoal,
     CounterSolution,
+    /// Reference to a complete module (Modular Extension).
+    /// It is only created for `supportedBy` references to module names.
+    #[serde(skip)]
+    Module,
 }
 
 impl Display for GsnNodeType {
@@ -39,12 +52,106 @@ impl Display for GsnNodeType {
     }
 }
 
+///
+/// Order of the references in `supportedBy` and `inContextOf`.
+/// This determines the horizontal order of elements on the same rank.
+///
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SiblingOrder {
+    /// G1, G10, G2
+    #[default]
+    Lexicographic,
+    /// G1, G2, G10
+    Natural,
+    /// Order as written in the input
+    Authored,
+}
+
+impl SiblingOrder {
+    ///
+    /// Sort `ids` according to this order.
+    ///
+    pub fn sort(self, ids: &mut [String]) {
+        match self {
+            SiblingOrder::Lexicographic => ids.sort(),
+            SiblingOrder::Natural => ids.sort_by(|a, b| natural_cmp(a, b)),
+            SiblingOrder::Authored => (),
+        }
+    }
+}
+
+///
+/// Compare `a` and `b` such that sequences of digits are compared by their numeric value.
+///
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (chunks_a, chunks_b) = (split_digits(a), split_digits(b));
+    chunks_a
+        .iter()
+        .zip(chunks_b.iter())
+        .map(|(x, y)| {
+            if x.starts_with(|c: char| c.is_ascii_digit())
+                && y.starts_with(|c: char| c.is_ascii_digit())
+            {
+                let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                x.len().cmp(&y.len()).then_with(|| x.cmp(y))
+            } else {
+                x.cmp(y)
+            }
+        })
+        .find(|o| o.is_ne())
+        .unwrap_or_else(|| chunks_a.len().cmp(&chunks_b.len()))
+        // Leading zeros make a difference, e.g., G01 and G1
+        .then_with(|| a.cmp(b))
+}
+
+///
+/// Split `text` into sequences of digits and sequences of other characters.
+///
+fn split_digits(text: &str) -> Vec<&str> {
+    let mut chunks = vec![];
+    let mut start = 0;
+    for (index, c) in text.char_indices().skip(1) {
+        let previous = text[..index].chars().next_back().unwrap(); // unwrap ok, since index > 0
+        if c.is_ascii_digit() != previous.is_ascii_digit() {
+            chunks.push(&text[start..index]);
+            start = index;
+        }
+    }
+    if start < text.len() {
+        chunks.push(&text[start..]);
+    }
+    chunks
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum GsnEdgeType<'a> {
     SupportedBy,
     InContextOf,
     ChallengesNode,
     ChallengesRelation(&'a str),
+    SupportedByPattern(EdgePattern<'a>),
+    InContextOfPattern(EdgePattern<'a>),
+}
+
+///
+/// Multiplicity of a relation (Argument Pattern Extension)
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Multiplicity<'a> {
+    /// n-ary relation with its label (e.g. `n` or `#Hazards`)
+    Multiple(&'a str),
+    /// optional relation
+    Optional,
+}
+
+///
+/// Pattern annotations of a relation (Argument Pattern Extension)
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EdgePattern<'a> {
+    pub multiplicity: Option<Multiplicity<'a>>,
+    pub choice: Option<&'a str>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
@@ -129,6 +236,16 @@ pub struct GsnNode {
     #[serde(default, skip_serializing_if = "<&bool>::not")]
     pub(crate) undeveloped: bool,
     #[serde(default, skip_serializing_if = "<&bool>::not")]
+    pub(crate) uninstantiated: bool,
+    #[serde(default, skip_serializing_if = "<&bool>::not")]
+    pub(crate) public: bool,
+    #[serde(
+        default,
+        deserialize_with = "deser_string_or_seq_string",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub(crate) suppress: Vec<String>,
+    #[serde(default, skip_serializing_if = "<&bool>::not")]
     pub(crate) defeated: bool,
     #[serde(
         default,
@@ -154,6 +271,24 @@ pub struct GsnNode {
         skip_serializing_if = "BTreeMap::is_empty"
     )]
     pub(crate) acp: BTreeMap<String, Vec<String>>,
+    #[serde(
+        default,
+        deserialize_with = "deser_multiplicity",
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub(crate) multiplicity: BTreeMap<String, String>,
+    #[serde(
+        default,
+        deserialize_with = "deser_string_or_seq_string",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub(crate) optional: Vec<String>,
+    #[serde(
+        default,
+        deserialize_with = "deser_acp",
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub(crate) choice: BTreeMap<String, Vec<String>>,
     #[serde(
         flatten,
         deserialize_with = "deser_additional",
@@ -162,6 +297,9 @@ pub struct GsnNode {
     pub(crate) additional: BTreeMap<String, String>,
     #[serde(skip_deserializing, skip_serializing)]
     pub(crate) module: String,
+    // Boxed, since locations are only needed for diagnostics
+    #[serde(skip_deserializing, skip_serializing)]
+    pub(crate) location: Option<Box<ElementLocation>>,
 }
 
 ///
@@ -231,6 +369,27 @@ where
     }
 }
 
+///
+/// Deserialize the multiplicity of relations.
+/// The label of a multiplicity can be either a string or a number.
+///
+fn deser_multiplicity<'de, D>(deserializer: D) -> Result<BTreeMap<String, String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let map: BTreeMap<String, Value> = Deserialize::deserialize(deserializer)?;
+    map.into_iter()
+        .map(|(k, v)| match v {
+            Value::String(label) => Ok((k, label)),
+            Value::Number(label) => Ok((k, label.to_string())),
+            _ => Err(de::Error::invalid_type(
+                de::Unexpected::Other("Unknown"),
+                &"string or number (please quote labels like n or y)",
+            )),
+        })
+        .collect()
+}
+
 ///
 /// Deserialize a challenges relation.
 /// Either a node, or a relation described by two nodes
@@ -321,6 +480,71 @@ where
 }
 
 impl GsnNode {
+    ///
+    /// Get the location of the element in its input file.
+    ///
+    pub fn get_location(&self) -> Option<&SourceLocation> {
+        self.location.as_ref().map(|l| &l.location)
+    }
+
+    ///
+    /// Get the location of the reference to `target`.
+    /// Falls back to the location of the element itself.
+    ///
+    pub fn get_reference_location(&self, target: &str) -> Option<&SourceLocation> {
+        self.location
+            .as_ref()
+            .map(|l| l.references.get(target).unwrap_or(&l.location))
+    }
+
+    ///
+    /// Get the location of the assurance claim point `acp`, or of its reference to `target` if given.
+    /// Falls back to the location of the element itself.
+    ///
+    pub fn get_acp_location(&self, acp: &str, target: Option<&str>) -> Option<&SourceLocation> {
+        self.location.as_ref().map(|l| {
+            l.acp
+                .get(acp)
+                .map(|a| {
+                    target
+                        .and_then(|t| a.references.get(t))
+                        .unwrap_or(&a.location)
+                })
+                .unwrap_or(&l.location)
+        })
+    }
+
+    ///
+    /// Get the location of the choice `choice`, or of its alternative `target` if given.
+    /// Falls back to the location of the element itself.
+    ///
+    pub fn get_choice_location(
+        &self,
+        choice: &str,
+        target: Option<&str>,
+    ) -> Option<&SourceLocation> {
+        self.location.as_ref().map(|l| {
+            l.choice
+                .get(choice)
+                .map(|c| {
+                    target
+                        .and_then(|t| c.references.get(t))
+                        .unwrap_or(&c.location)
+                })
+                .unwrap_or(&l.location)
+        })
+    }
+
+    ///
+    /// Get the location of the multiplicity of the relation to `target`.
+    /// Falls back to the location of the element itself.
+    ///
+    pub fn get_multiplicity_location(&self, target: &str) -> Option<&SourceLocation> {
+        self.location
+            .as_ref()
+            .map(|l| l.multiplicity.get(target).unwrap_or(&l.location))
+    }
+
     ///
     /// Get edges of node.
     /// Edge is tuple of target node id and edge type.
@@ -330,13 +554,27 @@ impl GsnNode {
         let mut es: Vec<(String, GsnEdgeType<'a>)> = self
             .in_context_of
             .iter()
-            .map(|target| (target.to_owned(), GsnEdgeType::InContextOf))
+            .map(|target| {
+                let pattern = self.get_edge_pattern(target);
+                if pattern == EdgePattern::default() {
+                    (target.to_owned(), GsnEdgeType::InContextOf)
+                } else {
+                    (target.to_owned(), GsnEdgeType::InContextOfPattern(pattern))
+                }
+            })
             .collect();
         edges.append(&mut es);
         let mut es: Vec<(String, GsnEdgeType<'a>)> = self
             .supported_by
             .iter()
-            .map(|target| (target.to_owned(), GsnEdgeType::SupportedBy))
+            .map(|target| {
+                let pattern = self.get_edge_pattern(target);
+                if pattern == EdgePattern::default() {
+                    (target.to_owned(), GsnEdgeType::SupportedBy)
+                } else {
+                    (target.to_owned(), GsnEdgeType::SupportedByPattern(pattern))
+                }
+            })
             .collect();
         edges.append(&mut es);
         let mut es: Vec<(String, GsnEdgeType<'a>)> = self
@@ -351,6 +589,30 @@ impl GsnNode {
         edges
     }
 
+    ///
+    /// Get the pattern annotations of the relation to `target`.
+    /// If a relation is both multiple and optional, multiplicity wins (see V16).
+    ///
+    pub fn get_edge_pattern<'a>(&'a self, target: &str) -> EdgePattern<'a> {
+        EdgePattern {
+            multiplicity: self
+                .multiplicity
+                .get(target)
+                .map(|label| Multiplicity::Multiple(label.as_str()))
+                .or_else(|| {
+                    self.optional
+                        .iter()
+                        .any(|o| o == target)
+                        .then_some(Multiplicity::Optional)
+                }),
+            choice: self
+                .choice
+                .iter()
+                .find(|(_, alternatives)| alternatives.iter().any(|a| a == target))
+                .map(|(label, _)| label.as_str()),
+        }
+    }
+
     ///
     /// Set the `node_type` based on prefix or explicit type.
     ///
@@ -392,6 +654,8 @@ impl<'a> DirectedGraphEdgeType<'a> for GsnEdgeType<'a> {
             GsnEdgeType::InContextOf => false,
             GsnEdgeType::ChallengesNode => false,
             GsnEdgeType::ChallengesRelation(_) => false,
+            GsnEdgeType::SupportedByPattern(_) => true,
+            GsnEdgeType::InContextOfPattern(_) => false,
         }
     }
 
@@ -401,6 +665,8 @@ impl<'a> DirectedGraphEdgeType<'a> for GsnEdgeType<'a> {
             GsnEdgeType::InContextOf => true,
             GsnEdgeType::ChallengesNode => false,
             GsnEdgeType::ChallengesRelation(_) => false,
+            GsnEdgeType::SupportedByPattern(_) => false,
+            GsnEdgeType::InContextOfPattern(_) => true,
         }
     }
     fn is_inverted_child_edge(&self) -> bool {
@@ -409,10 +675,120 @@ impl<'a> DirectedGraphEdgeType<'a> for GsnEdgeType<'a> {
             GsnEdgeType::InContextOf => false,
             GsnEdgeType::ChallengesNode => true,
             GsnEdgeType::ChallengesRelation(_) => true,
+            GsnEdgeType::SupportedByPattern(_) => false,
+            GsnEdgeType::InContextOfPattern(_) => false,
         }
     }
 }
 
+///
+/// Attributes of an element. They cannot be used as layers.
+///
+pub const ELEMENT_ATTRIBUTES: [&str; 20] = [
+    "text",
+    "inContextOf",
+    "supportedBy",
+    "challenges",
+    "undeveloped",
+    "uninstantiated",
+    "public",
+    "suppress",
+    "defeated",
+    "defeatedRelation",
+    "classes",
+    "url",
+    "rankIncrement",
+    "horizontalIndex",
+    "nodeType",
+    "charWrap",
+    "acp",
+    "multiplicity",
+    "optional",
+    "choice",
+];
+
+///
+/// Attributes of the module information.
+///
+pub const MODULE_ATTRIBUTES: [&str; 13] = [
+    "name",
+    "brief",
+    "extends",
+    "horizontalIndex",
+    "rankIncrement",
+    "charWrap",
+    "siblingOrder",
+    "stylesheets",
+    "uses",
+    "interface",
+    "roots",
+    "contract",
+    "suppress",
+];
+
+///
+/// Edit distance of `a` and `b` (optimal string alignment distance).
+/// Insertions, deletions, substitutions and transpositions of adjacent characters count as one edit.
+///
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+///
+/// Find the reserved attribute in `attributes` that `key` is most likely a misspelling of.
+/// Case is ignored. Short attributes allow only a single edit.
+///
+pub fn find_misspelled_attribute<'a>(key: &str, attributes: &[&'a str]) -> Option<&'a str> {
+    let key = key.to_lowercase();
+    attributes
+        .iter()
+        .map(|&a| (edit_distance(&key, &a.to_lowercase()), a))
+        .filter(|&(distance, a)| distance <= if a.len() <= 4 { 1 } else { 2 })
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, a)| a)
+}
+
+///
+/// Find IDs in `candidates` that are spelled similar to `id`.
+///
+/// The candidates are ranked by their edit distance (ignoring case) and the best three are returned.
+/// Longer IDs allow more edits, short IDs like G1 only differences in case.
+///
+pub fn find_similar_ids<'a>(
+    id: &str,
+    candidates: impl Iterator<Item = &'a String>,
+) -> Vec<&'a str> {
+    let lower_id = id.to_lowercase();
+    let max_distance = id.chars().count() / 4;
+    let mut similar = candidates
+        .filter(|c| c.as_str() != id)
+        .map(|c| (edit_distance(&lower_id, &c.to_lowercase()), c.as_str()))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .collect::<Vec<_>>();
+    similar.sort();
+    similar.into_iter().take(3).map(|(_, c)| c).collect()
+}
+
 ///
 /// Get the node type from a prefix
 ///
@@ -446,10 +822,24 @@ pub struct ModuleInformation {
     pub(crate) rank_increment: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) char_wrap: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) sibling_order: Option<SiblingOrder>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub(crate) stylesheets: Vec<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub(crate) uses: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) interface: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) roots: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) contract: Vec<Contract>,
+    #[serde(
+        default,
+        deserialize_with = "deser_string_or_seq_string",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub(crate) suppress: Vec<String>,
     #[serde(
         flatten,
         deserialize_with = "deser_additional",
@@ -465,7 +855,12 @@ impl ModuleInformation {
             brief: None,
             extends: vec![],
             uses: vec![],
+            interface: vec![],
+            roots: vec![],
+            contract: vec![],
+            suppress: vec![],
             char_wrap: None,
+            sibling_order: None,
             stylesheets: vec![],
             horizontal_index: None,
             rank_increment: None,
@@ -504,10 +899,34 @@ impl Display for Origin {
 #[derive(Default)]
 pub struct Module {
     pub orig_file_name: String,
+    /// Index of the document (starting at 1), if the file contains several documents
+    pub document: Option<usize>,
     pub canonical_path: Option<PathBuf>,
     pub output_path: Option<String>,
     pub origin: Origin,
     pub meta: ModuleInformation,
+    pub location: Option<SourceLocation>,
+    /// Effective order of the module, i.e., the global order, if the module does not define one.
+    pub sibling_order: SiblingOrder,
+}
+
+impl Module {
+    ///
+    /// Get the file and, if there are several documents in the file, the document of the module.
+    ///
+    pub fn source(&self) -> String {
+        get_source_name(&self.orig_file_name, self.document)
+    }
+}
+
+///
+/// Get the name of `document` in `file` as used in messages.
+///
+pub fn get_source_name(file: &str, document: Option<usize>) -> String {
+    match document {
+        Some(document) => format!("{file} (document {document})"),
+        None => file.to_owned(),
+    }
 }
 
 pub trait FindModuleByPath {
@@ -524,9 +943,30 @@ impl FindModuleByPath for BTreeMap<String, Module> {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ExtendsModule {
     pub module: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub develops: BTreeMap<String, Vec<String>>,
 }
 
+///
+/// An inter-module contract (Modular Extension).
+/// The `goals` of one module are satisfied by the goals in `satisfied_by` of another module
+/// in the context of elements of the contract module.
+///
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Contract {
+    #[serde(deserialize_with = "deser_string_or_seq_string")]
+    pub goals: Vec<String>,
+    #[serde(deserialize_with = "deser_string_or_seq_string")]
+    pub satisfied_by: Vec<String>,
+    #[serde(
+        default,
+        deserialize_with = "deser_string_or_seq_string",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub in_context_of: Vec<String>,
+}
+
 ///
 /// Perform the extension of modules.
 ///
@@ -539,8 +979,10 @@ pub fn extend_modules(
     for (module_name, module_info) in modules {
         for ext in &module_info.meta.extends {
             if !modules.contains_key(&ext.module) {
-                diags.add_error(
+                diags.add_error_at(
                     Some(module_name),
+                    module_info.location.as_ref(),
+                    &[],
                     format!(
                         "C09: Module {} is not found, but is supposed to be extended by module {}.",
                         ext.module, module_name
@@ -551,14 +993,18 @@ pub fn extend_modules(
             for (foreign_id, local_ids) in &ext.develops {
                 if let Some(foreign_node) = nodes.get_mut(foreign_id) {
                     if foreign_node.module != ext.module {
-                        diags.add_error(
+                        diags.add_error_at(
                                     Some(module_name),
+                                    module_info.location.as_ref(),
+                                    &[foreign_id.as_str()],
                                     format!("C10: Element {} does not exist in module {}, but is supposed to be extended by {}.", foreign_id, ext.module, local_ids.join(",")),
                                 );
                         errors += 1;
                     } else if !foreign_node.undeveloped {
-                        diags.add_error(
+                        diags.add_error_at(
                                     Some(module_name),
+                                    module_info.location.as_ref(),
+                                    &[foreign_id.as_str()],
                                     format!("C10: Element {} is not undeveloped, but is supposed to be extended by {}.", foreign_id, local_ids.join(",")),
                                 );
                         errors += 1;
@@ -567,8 +1013,10 @@ pub fn extend_modules(
                         foreign_node.undeveloped = false;
                     }
                 } else {
-                    diags.add_error(
+                    diags.add_error_at(
                         Some(module_name),
+                        module_info.location.as_ref(),
+                        &[foreign_id.as_str()],
                         format!(
                             "C10: Element {} does not exist, but is supposed to be extended by {}.",
                             foreign_id,
@@ -583,6 +1031,265 @@ pub fn extend_modules(
     if errors == 0 { Ok(()) } else { Err(()) }
 }
 
+///
+/// Add elements for `supportedBy` references to module names.
+/// Such a module reference is supported by the root elements of the referenced module.
+/// These are the declared `roots` of the module, or its unreferenced elements otherwise.
+///
+pub fn add_module_references(
+    nodes: &mut BTreeMap<String, GsnNode>,
+    modules: &BTreeMap<String, Module>,
+) {
+    let referenced_modules: BTreeSet<String> = nodes
+        .values()
+        .flat_map(|n| n.supported_by.iter())
+        .filter(|&r| !nodes.contains_key(r) && modules.contains_key(r))
+        .cloned()
+        .collect();
+    for module_name in referenced_modules {
+        let module_nodes: BTreeMap<String, GsnNode> = nodes
+            .iter()
+            .filter(|(_, n)| n.module == module_name)
+            .map(|(id, n)| (id.to_owned(), n.to_owned()))
+            .collect();
+        // unwrap is ok, since module names are filtered above
+        let meta = &modules.get(&module_name).unwrap().meta;
+        // Declared roots take precedence over the unreferenced elements.
+        let declared_roots: Vec<String> = meta
+            .roots
+            .iter()
+            .filter(|r| module_nodes.contains_key(*r))
+            .cloned()
+            .collect();
+        let module_reference = GsnNode {
+            text: meta.brief.to_owned().unwrap_or_default(),
+            supported_by: if declared_roots.is_empty() {
+                get_root_nodes(&module_nodes)
+            } else {
+                declared_roots
+            },
+            node_type: Some(GsnNodeType::Module),
+            module: module_name.to_owned(),
+            ..Default::default()
+        };
+        nodes.insert(module_name, module_reference);
+    }
+}
+
+///
+/// Get the modules that restrict which of their elements can be referenced from other modules,
+/// i.e., the modules with at least one public element.
+///
+pub fn get_restricted_modules(nodes: &BTreeMap<String, GsnNode>) -> BTreeSet<String> {
+    nodes
+        .values()
+        .filter(|n| n.public)
+        .map(|n| n.module.to_owned())
+        .collect()
+}
+
+///
+/// Check if the element `id` can be referenced from other modules.
+/// Modules without any public element, i.e., not in `restricted_modules`, keep all their elements public.
+///
+pub fn is_public(
+    nodes: &BTreeMap<String, GsnNode>,
+    restricted_modules: &BTreeSet<String>,
+    id: &str,
+) -> bool {
+    nodes.get(id).is_some_and(|node| {
+        node.public
+            || node.node_type == Some(GsnNodeType::Module)
+            || !restricted_modules.contains(&node.module)
+    })
+}
+
+///
+/// Apply the inter-module contracts.
+/// The goals of a contract are supported by the goals satisfying them
+/// and put in the context of the contract's context elements.
+///
+pub fn apply_contracts(
+    diags: &mut Diagnostics,
+    nodes: &mut BTreeMap<String, GsnNode>,
+    modules: &BTreeMap<String, Module>,
+) -> Result<(), ()> {
+    let mut errors = 0;
+    let mut satisfied_goals = BTreeSet::new();
+    let restricted_modules = get_restricted_modules(nodes);
+    for (module_name, module_info) in modules {
+        for contract in &module_info.meta.contract {
+            let mut contract_errors = 0;
+            for id in contract.goals.iter().chain(contract.satisfied_by.iter()) {
+                match nodes.get(id) {
+                    None => {
+                        diags.add_error_at(
+                            Some(module_name),
+                            module_info.location.as_ref(),
+                            &[id.as_str()],
+                            format!("C20: Element {id} does not exist, but is part of the contract in module {module_name}."),
+                        );
+                        contract_errors += 1;
+                    }
+                    Some(node) if &node.module == module_name => {
+                        diags.add_error_at(
+                            Some(module_name),
+                            module_info.location.as_ref(),
+                            &[id.as_str()],
+                            format!("C20: Element {id} is part of the contract in module {module_name}, but does not belong to another module."),
+                        );
+                        contract_errors += 1;
+                    }
+                    Some(node) if node.node_type != Some(GsnNodeType::Goal) => {
+                        diags.add_error_at(
+                            Some(module_name),
+                            module_info.location.as_ref(),
+                            &[id.as_str()],
+                            format!("C20: Element {id} is not a goal, but is part of the contract in module {module_name}."),
+                        );
+                        contract_errors += 1;
+                    }
+                    Some(node) if !is_public(nodes, &restricted_modules, id) => {
+                        diags.add_error_at(
+                            Some(module_name),
+                            module_info.location.as_ref(),
+                            &[id.as_str()],
+                            format!(
+                                "C20: Element {id} of module {} is not public, but is part of the contract in module {module_name}.",
+                                node.module
+                            ),
+                        );
+                        contract_errors += 1;
+                    }
+                    Some(_) => (),
+                }
+            }
+            let goal_modules = contract
+                .goals
+                .iter()
+                .filter_map(|id| nodes.get(id))
+                .map(|n| n.module.as_str())
+                .collect::<BTreeSet<_>>();
+            for id in &contract.satisfied_by {
+                if let Some(node) = nodes.get(id)
+                    && goal_modules.contains(node.module.as_str())
+                {
+                    diags.add_error_at(
+                        Some(module_name),
+                        module_info.location.as_ref(),
+                        &[id.as_str()],
+                        format!(
+                            "C20: Element {id} belongs to module {} like the goals it satisfies, but is part of the contract in module {module_name}.",
+                            node.module
+                        ),
+                    );
+                    contract_errors += 1;
+                }
+            }
+            for id in &contract.goals {
+                if let Some(node) = nodes.get(id)
+                    && !node.undeveloped
+                    && !satisfied_goals.contains(id)
+                {
+                    diags.add_error_at(
+                        Some(module_name),
+                        module_info.location.as_ref(),
+                        &[id.as_str()],
+                        format!("C20: Element {id} is not undeveloped, but is supposed to be satisfied by the contract in module {module_name}."),
+                    );
+                    contract_errors += 1;
+                }
+            }
+            for id in &contract.in_context_of {
+                match nodes.get(id) {
+                    Some(node) if &node.module == module_name => {
+                        if !matches!(
+                            node.node_type,
+                            Some(GsnNodeType::Context)
+                                | Some(GsnNodeType::Assumption)
+                                | Some(GsnNodeType::Justification)
+                        ) {
+                            diags.add_error_at(
+                                Some(module_name),
+                                module_info.location.as_ref(),
+                                &[id.as_str()],
+                                format!("C20: Element {id} is neither a context, an assumption nor a justification, but is used as context of the contract in module {module_name}."),
+                            );
+                            contract_errors += 1;
+                        }
+                    }
+                    _ => {
+                        diags.add_error_at(
+                            Some(module_name),
+                            module_info.location.as_ref(),
+                            &[id.as_str()],
+                            format!("C20: Element {id} does not exist in module {module_name}, but is used as context of its contract."),
+                        );
+                        contract_errors += 1;
+                    }
+                }
+            }
+            if contract_errors == 0 {
+                for id in &contract.goals {
+                    // unwrap is ok, since existence is checked above
+                    let goal = nodes.get_mut(id).unwrap();
+                    goal.undeveloped = false;
+                    goal.supported_by
+                        .extend(contract.satisfied_by.iter().cloned());
+                    goal.in_context_of
+                        .extend(contract.in_context_of.iter().cloned());
+                    for ids in [&mut goal.supported_by, &mut goal.in_context_of] {
+                        module_info.sibling_order.sort(ids);
+                        // Keep the first occurrence, since the order might be the authored one
+                        let mut seen = BTreeSet::new();
+                        ids.retain(|id| seen.insert(id.to_owned()));
+                    }
+                    satisfied_goals.insert(id.to_owned());
+                }
+            }
+            errors += contract_errors;
+        }
+    }
+    if errors == 0 { Ok(()) } else { Err(()) }
+}
+
+///
+/// Get the elements of the tree starting at `root`.
+/// These are all elements reachable from `root` and the elements challenging them.
+///
+pub fn get_root_tree(nodes: &BTreeMap<String, GsnNode>, root: &str) -> BTreeMap<String, GsnNode> {
+    let mut tree: BTreeSet<&str> = BTreeSet::new();
+    let mut pending = vec![root.to_owned()];
+    while !pending.is_empty() {
+        while let Some(id) = pending.pop() {
+            if let Some((id, node)) = nodes.get_key_value(&id)
+                && tree.insert(id)
+            {
+                pending.extend(node.get_edges().into_iter().map(|(target, _)| target));
+            }
+        }
+        // Challenging elements are not reachable from the root, but belong to the tree.
+        pending = nodes
+            .iter()
+            .filter(|(id, n)| {
+                !tree.contains(id.as_str())
+                    && match &n.challenges {
+                        Some(Challenge::Node(t)) | Some(Challenge::Relation((t, _))) => {
+                            tree.contains(t.as_str())
+                        }
+                        None => false,
+                    }
+            })
+            .map(|(id, _)| id.to_owned())
+            .collect();
+    }
+    nodes
+        .iter()
+        .filter(|(id, _)| tree.contains(id.as_str()))
+        .map(|(id, n)| (id.to_owned(), n.to_owned()))
+        .collect()
+}
+
 ///
 /// Get root nodes
 /// These are the unreferenced nodes.
@@ -716,6 +1423,45 @@ mod test {
         }
     }
 
+    #[test]
+    fn root_tree() {
+        let mut nodes = BTreeMap::<String, GsnNode>::new();
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                supported_by: vec!["S1".to_owned()],
+                in_context_of: vec!["C1".to_owned()],
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "G2".to_owned(),
+            GsnNode {
+                in_context_of: vec!["C1".to_owned()],
+                ..Default::default()
+            },
+        );
+        nodes.insert("S1".to_owned(), GsnNode::default());
+        nodes.insert("C1".to_owned(), GsnNode::default());
+        nodes.insert(
+            "CG1".to_owned(),
+            GsnNode {
+                challenges: Some(Challenge::Node("S1".to_owned())),
+                supported_by: vec!["Sn1".to_owned()],
+                ..Default::default()
+            },
+        );
+        nodes.insert("Sn1".to_owned(), GsnNode::default());
+        assert_eq!(
+            get_root_tree(&nodes, "G1").keys().collect::<Vec<_>>(),
+            ["C1", "CG1", "G1", "S1", "Sn1"]
+        );
+        assert_eq!(
+            get_root_tree(&nodes, "G2").keys().collect::<Vec<_>>(),
+            ["C1", "G2"]
+        );
+    }
+
     #[test]
     fn dummy_tryinto() {
         let g = GsnDocument::ModuleInformation(ModuleInformation {
@@ -814,6 +1560,63 @@ C1:
         Ok(())
     }
 
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("supportedBy", "supportedBy"), 0);
+        assert_eq!(edit_distance("supportBy", "supportedBy"), 2);
+        assert_eq!(edit_distance("udneveloped", "undeveloped"), 1);
+        assert_eq!(edit_distance("", "url"), 3);
+    }
+
+    #[test]
+    fn similar_ids() {
+        let ids = [
+            "Sn_HAZ_017",
+            "Sn_HAZ_018",
+            "Sn_HAZ_117",
+            "G1",
+            "G2",
+            "g3",
+            "Sn_SAF_001",
+        ]
+        .map(|id| id.to_owned());
+        assert_eq!(
+            find_similar_ids("Sn_HAZ_071", ids.iter()),
+            vec!["Sn_HAZ_017", "Sn_HAZ_018", "Sn_HAZ_117"]
+        );
+        assert_eq!(
+            find_similar_ids("sn_haz_018", ids.iter()),
+            vec!["Sn_HAZ_018", "Sn_HAZ_017", "Sn_HAZ_117"]
+        );
+        assert_eq!(find_similar_ids("G3", ids.iter()), vec!["g3"]);
+        assert!(find_similar_ids("G4", ids.iter()).is_empty());
+    }
+
+    #[test]
+    fn misspelled_attributes() {
+        assert_eq!(
+            find_misspelled_attribute("supportBy", &ELEMENT_ATTRIBUTES),
+            Some("supportedBy")
+        );
+        assert_eq!(
+            find_misspelled_attribute("inContextof", &ELEMENT_ATTRIBUTES),
+            Some("inContextOf")
+        );
+        assert_eq!(
+            find_misspelled_attribute("rankIncrment", &ELEMENT_ATTRIBUTES),
+            Some("rankIncrement")
+        );
+        assert_eq!(
+            find_misspelled_attribute("extend", &MODULE_ATTRIBUTES),
+            Some("extends")
+        );
+        assert_eq!(
+            find_misspelled_attribute("layer1", &ELEMENT_ATTRIBUTES),
+            None
+        );
+        assert_eq!(find_misspelled_attribute("note", &ELEMENT_ATTRIBUTES), None);
+    }
+
     #[test]
     fn deser_acp1() {
         let gsn = r#"
@@ -870,4 +1673,240 @@ G1:
         assert_eq!(format!("{:?}", GsnEdgeType::SupportedBy), "SupportedBy");
         assert_eq!(format!("{:?}", GsnEdgeType::InContextOf), "InContextOf");
     }
+
+    #[test]
+    fn module_reference_added() {
+        let mut nodes = BTreeMap::from([
+            (
+                "G1".to_owned(),
+                GsnNode {
+                    supported_by: vec!["B".to_owned()],
+                    node_type: Some(GsnNodeType::Goal),
+                    module: "A".to_owned(),
+                    ..Default::default()
+                },
+            ),
+            (
+                "G2".to_owned(),
+                GsnNode {
+                    undeveloped: true,
+                    node_type: Some(GsnNodeType::Goal),
+                    module: "B".to_owned(),
+                    ..Default::default()
+                },
+            ),
+        ]);
+        let mut meta = ModuleInformation::new("B".to_owned());
+        meta.brief = Some("Module B".to_owned());
+        let modules = BTreeMap::from([(
+            "B".to_owned(),
+            Module {
+                meta,
+                ..Default::default()
+            },
+        )]);
+        add_module_references(&mut nodes, &modules);
+        let module_reference = nodes.get("B").unwrap();
+        assert_eq!(module_reference.node_type, Some(GsnNodeType::Module));
+        assert_eq!(module_reference.module, "B");
+        assert_eq!(module_reference.text, "Module B");
+        assert_eq!(module_reference.supported_by, vec!["G2".to_owned()]);
+    }
+
+    #[test]
+    fn module_reference_element_precedence() {
+        let mut nodes = BTreeMap::from([
+            (
+                "G1".to_owned(),
+                GsnNode {
+                    supported_by: vec!["B".to_owned()],
+                    node_type: Some(GsnNodeType::Goal),
+                    module: "A".to_owned(),
+                    ..Default::default()
+                },
+            ),
+            (
+                "B".to_owned(),
+                GsnNode {
+                    undeveloped: true,
+                    node_type: Some(GsnNodeType::Goal),
+                    module: "B".to_owned(),
+                    ..Default::default()
+                },
+            ),
+        ]);
+        let modules = BTreeMap::from([(
+            "B".to_owned(),
+            Module {
+                meta: ModuleInformation::new("B".to_owned()),
+                ..Default::default()
+            },
+        )]);
+        add_module_references(&mut nodes, &modules);
+        assert_eq!(nodes.get("B").unwrap().node_type, Some(GsnNodeType::Goal));
+    }
+
+    fn contract_fixture() -> (BTreeMap<String, GsnNode>, BTreeMap<String, Module>) {
+        let nodes = BTreeMap::from([
+            (
+                "G1".to_owned(),
+                GsnNode {
+                    undeveloped: true,
+                    node_type: Some(GsnNodeType::Goal),
+                    module: "A".to_owned(),
+                    ..Default::default()
+                },
+            ),
+            (
+                "G2".to_owned(),
+                GsnNode {
+                    undeveloped: true,
+                    node_type: Some(GsnNodeType::Goal),
+                    module: "B".to_owned(),
+                    ..Default::default()
+                },
+            ),
+            (
+                "C1".to_owned(),
+                GsnNode {
+                    node_type: Some(GsnNodeType::Context),
+                    module: "Contract".to_owned(),
+                    ..Default::default()
+                },
+            ),
+        ]);
+        let mut meta = ModuleInformation::new("Contract".to_owned());
+        meta.contract = vec![Contract {
+            goals: vec!["G1".to_owned()],
+            satisfied_by: vec!["G2".to_owned()],
+            in_context_of: vec!["C1".to_owned()],
+        }];
+        let modules = BTreeMap::from([(
+            "Contract".to_owned(),
+            Module {
+                meta,
+                ..Default::default()
+            },
+        )]);
+        (nodes, modules)
+    }
+
+    #[test]
+    fn contract_applied() {
+        let mut d = Diagnostics::default();
+        let (mut nodes, modules) = contract_fixture();
+        assert!(apply_contracts(&mut d, &mut nodes, &modules).is_ok());
+        assert_eq!(d.messages.len(), 0);
+        let goal = nodes.get("G1").unwrap();
+        assert!(!goal.undeveloped);
+        assert_eq!(goal.supported_by, vec!["G2".to_owned()]);
+        assert_eq!(goal.in_context_of, vec!["C1".to_owned()]);
+    }
+
+    #[test]
+    fn contract_private_goal() {
+        let mut d = Diagnostics::default();
+        let (mut nodes, modules) = contract_fixture();
+        nodes.insert(
+            "G3".to_owned(),
+            GsnNode {
+                public: true,
+                node_type: Some(GsnNodeType::Goal),
+                module: "B".to_owned(),
+                ..Default::default()
+            },
+        );
+        assert!(apply_contracts(&mut d, &mut nodes, &modules).is_err());
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(
+            d.messages[0].msg,
+            "C20: Element G2 of module B is not public, but is part of the contract in module Contract."
+        );
+        assert!(nodes.get("G1").unwrap().undeveloped);
+    }
+
+    #[test]
+    fn contract_within_module() {
+        let mut d = Diagnostics::default();
+        let (mut nodes, modules) = contract_fixture();
+        nodes.get_mut("G2").unwrap().module = "A".to_owned();
+        assert!(apply_contracts(&mut d, &mut nodes, &modules).is_err());
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(
+            d.messages[0].msg,
+            "C20: Element G2 belongs to module A like the goals it satisfies, but is part of the contract in module Contract."
+        );
+        assert!(nodes.get("G1").unwrap().undeveloped);
+    }
+
+    #[test]
+    fn contract_developed_goal() {
+        let mut d = Diagnostics::default();
+        let (mut nodes, modules) = contract_fixture();
+        nodes.get_mut("G1").unwrap().undeveloped = false;
+        assert!(apply_contracts(&mut d, &mut nodes, &modules).is_err());
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(
+            d.messages[0].msg,
+            "C20: Element G1 is not undeveloped, but is supposed to be satisfied by the contract in module Contract."
+        );
+    }
+
+    #[test]
+    fn pattern_edges() -> Result<()> {
+        let gsn = r#"
+S1:
+  text: Argument over hazards
+  supportedBy: [G1, G2, G3]
+  inContextOf: [C1]
+  multiplicity:
+    G1: 3
+  optional: G2
+  choice:
+    1 of 2: [G2, G3]
+"#;
+        let res: BTreeMap<String, GsnDocument> = serde_saphyr::from_str(gsn)?;
+        let n: &GsnNode = res.get("S1").unwrap().try_into().unwrap();
+        assert_eq!(
+            n.get_edges(),
+            vec![
+                ("C1".to_owned(), GsnEdgeType::InContextOf),
+                (
+                    "G1".to_owned(),
+                    GsnEdgeType::SupportedByPattern(EdgePattern {
+                        multiplicity: Some(Multiplicity::Multiple("3")),
+                        choice: None
+                    })
+                ),
+                (
+                    "G2".to_owned(),
+                    GsnEdgeType::SupportedByPattern(EdgePattern {
+                        multiplicity: Some(Multiplicity::Optional),
+                        choice: Some("1 of 2")
+                    })
+                ),
+                (
+                    "G3".to_owned(),
+                    GsnEdgeType::SupportedByPattern(EdgePattern {
+                        multiplicity: None,
+                        choice: Some("1 of 2")
+                    })
+                ),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sibling_order() {
+        let ids = ["G10", "G2", "G1", "Sn1", "G02", "G1a", "G"].map(|id| id.to_owned());
+        let mut sorted = ids.to_vec();
+        SiblingOrder::Lexicographic.sort(&mut sorted);
+        assert_eq!(sorted, ["G", "G02", "G1", "G10", "G1a", "G2", "Sn1"]);
+        SiblingOrder::Natural.sort(&mut sorted);
+        assert_eq!(sorted, ["G", "G1", "G1a", "G02", "G2", "G10", "Sn1"]);
+        let mut sorted = ids.to_vec();
+        SiblingOrder::Authored.sort(&mut sorted);
+        assert_eq!(sorted, ids);
+    }
 }
@@ -1,43 +1,68 @@
-use super::{Challenge, GsnEdgeType, GsnNode, GsnNodeType};
+use super::{
+    Challenge, ELEMENT_ATTRIBUTES, GsnEdgeType, GsnNode, GsnNodeType, Module, find_similar_ids,
+    get_restricted_modules, is_public,
+};
 use crate::{diagnostics::Diagnostics, dirgraph::DirectedGraph};
 use std::collections::BTreeMap;
 
 ///
 /// Entry function to all checks.
 ///
+/// `unloaded_elements` maps IDs of elements in files that are not loaded to these files.
+/// It is only used to improve the messages about unresolved references.
 ///
 pub fn check_nodes(
     diag: &mut Diagnostics,
     nodes: &BTreeMap<String, GsnNode>,
     excluded_modules: &[&str],
+    modules: &BTreeMap<String, Module>,
+    unloaded_elements: &BTreeMap<String, String>,
 ) -> Result<(), ()> {
-    check_node_references(diag, nodes, excluded_modules)?;
-    check_root_nodes(diag, nodes).and_then(|_| {
-        let edges: BTreeMap<String, Vec<(String, GsnEdgeType)>> = nodes
-            .iter()
-            .map(|(id, node)| (id.to_owned(), node.get_edges()))
-            .collect();
-        let graph = DirectedGraph::new(nodes, &edges);
-        check_cycles(diag, &graph).and_then(|_| check_unreachable(diag, &graph))
-    })
+    diag.check(|d| check_node_references(d, nodes, excluded_modules, unloaded_elements))?;
+    diag.check(|d| check_root_nodes(d, nodes, modules).map(|_| ()))?;
+    let edges: BTreeMap<String, Vec<(String, GsnEdgeType)>> = nodes
+        .iter()
+        .map(|(id, node)| (id.to_owned(), node.get_edges()))
+        .collect();
+    let graph = DirectedGraph::new(nodes, &edges);
+    diag.check(|d| check_cycles(d, &graph))?;
+    diag.check(|d| check_unreachable(d, &graph))
 }
 
 ///
 /// Check if there is one and only one unreferenced node
 /// and if it is a Goal
 ///
+/// Modules that declare their roots are checked on their own.
+/// Their elements are not considered for the single unreferenced node.
 ///
 fn check_root_nodes(
     diag: &mut Diagnostics,
     nodes: &BTreeMap<String, GsnNode>,
+    modules: &BTreeMap<String, Module>,
 ) -> Result<Vec<String>, ()> {
-    let root_nodes = super::get_root_nodes(nodes);
-    match root_nodes.len() {
+    let declares_roots = |module: &str| {
+        modules
+            .get(module)
+            .is_some_and(|m| !m.meta.roots.is_empty())
+    };
+    let declared_roots = check_declared_root_nodes(diag, nodes, modules);
+    // unwrap is ok, since root nodes are taken from nodes
+    let root_nodes = super::get_root_nodes(nodes)
+        .into_iter()
+        .filter(|id| !declares_roots(&nodes.get(id).unwrap().module))
+        .collect::<Vec<_>>();
+    let has_undeclared_roots = nodes.values().any(|n| !declares_roots(&n.module));
+    let root_nodes = match root_nodes.len() {
         x if x > 1 => {
             let mut wn = root_nodes.to_vec();
             wn.sort();
-            diag.add_warning(
+            diag.add_warning_at(
                 None,
+                wn.first()
+                    .and_then(|n| nodes.get(n))
+                    .and_then(|n| n.get_location()),
+                &wn.iter().map(String::as_str).collect::<Vec<_>>(),
                 format!(
                     "C01: There is more than one unreferenced element: {}.",
                     wn.join(", ")
@@ -47,9 +72,12 @@ fn check_root_nodes(
         }
         1 => {
             let rootn = root_nodes.first().unwrap(); // unwrap is ok, since we just checked that there is an element in Vec
-            if nodes.get(rootn).unwrap().node_type != Some(GsnNodeType::Goal) {
-                diag.add_error(
+            let root_node = nodes.get(rootn).unwrap();
+            if root_node.node_type != Some(GsnNodeType::Goal) {
+                diag.add_error_at(
                     None,
+                    root_node.get_location(),
+                    &[rootn.as_str()],
                     format!("C02: The root element should be a goal, but {rootn} was found."),
                 );
                 Err(())
@@ -57,7 +85,7 @@ fn check_root_nodes(
                 Ok(root_nodes)
             }
         }
-        x if x == 0 && !nodes.is_empty() => {
+        x if x == 0 && has_undeclared_roots => {
             diag.add_error(
                 None,
                 "C01: There are no unreferenced elements found.".to_owned(),
@@ -68,7 +96,100 @@ fn check_root_nodes(
             // Ignore empty document. root_nodes is empty here.
             Ok(root_nodes)
         }
+    };
+    Ok([declared_roots?, root_nodes?].concat())
+}
+
+///
+/// Check the roots declared by the modules.
+///
+/// Declared roots must exist in their module, must not be referenced within their module and should be goals.
+/// Each element that is not referenced within its module and not declared as root is reported.
+/// References from other modules do not count, since these modules use the roots.
+///
+fn check_declared_root_nodes(
+    diag: &mut Diagnostics,
+    nodes: &BTreeMap<String, GsnNode>,
+    modules: &BTreeMap<String, Module>,
+) -> Result<Vec<String>, ()> {
+    let mut errors = 0;
+    let mut roots = vec![];
+    for (module_name, module) in modules.iter().filter(|(_, m)| !m.meta.roots.is_empty()) {
+        // Module references are supported by the roots of the module.
+        let module_elements = nodes
+            .iter()
+            .filter(|(_, n)| &n.module == module_name && n.node_type != Some(GsnNodeType::Module))
+            .collect::<Vec<_>>();
+        let is_referenced = |id: &String| {
+            module_elements
+                .iter()
+                .any(|(_, n)| n.supported_by.contains(id) || n.in_context_of.contains(id))
+        };
+        for id in &module.meta.roots {
+            let Some(node) = nodes.get(id).filter(|n| &n.module == module_name) else {
+                diag.add_error_at(
+                    Some(module_name),
+                    module.location.as_ref(),
+                    &[id.as_str()],
+                    format!(
+                        "C01: Element {id} is declared as root of module {module_name}, but does not exist in that module."
+                    ),
+                );
+                errors += 1;
+                continue;
+            };
+            let referencing = module_elements
+                .iter()
+                .filter(|(_, n)| n.supported_by.contains(id) || n.in_context_of.contains(id))
+                .map(|(r, _)| r.as_str())
+                .collect::<Vec<_>>();
+            if !referencing.is_empty() {
+                diag.add_error_at(
+                    Some(module_name),
+                    node.get_location(),
+                    &[id.as_str()],
+                    format!(
+                        "C01: Element {id} is declared as root of module {module_name}, but is referenced by {}.",
+                        referencing.join(", ")
+                    ),
+                );
+                errors += 1;
+            } else if node.challenges.is_some() {
+                diag.add_error_at(
+                    Some(module_name),
+                    node.get_location(),
+                    &[id.as_str()],
+                    format!(
+                        "C01: Element {id} is declared as root of module {module_name}, but challenges another element."
+                    ),
+                );
+                errors += 1;
+            } else if node.node_type != Some(GsnNodeType::Goal) {
+                diag.add_error_at(
+                    Some(module_name),
+                    node.get_location(),
+                    &[id.as_str()],
+                    format!("C02: The root element should be a goal, but {id} was found."),
+                );
+                errors += 1;
+            } else {
+                roots.push(id.to_owned());
+            }
+        }
+        for (id, node) in module_elements.iter().filter(|(id, n)| {
+            !module.meta.roots.contains(id) && n.challenges.is_none() && !is_referenced(id)
+        }) {
+            diag.add_warning_at(
+                Some(module_name),
+                node.get_location(),
+                &[id.as_str()],
+                format!(
+                    "C01: Element {id} is unreferenced, but not declared in the roots of module {module_name}."
+                ),
+            );
+        }
     }
+    if errors == 0 { Ok(roots) } else { Err(()) }
 }
 
 ///
@@ -79,6 +200,7 @@ fn check_node_references(
     diag: &mut Diagnostics,
     nodes: &BTreeMap<String, GsnNode>,
     excluded_modules: &[&str],
+    unloaded_elements: &BTreeMap<String, String>,
 ) -> Result<(), ()> {
     nodes
         .iter()
@@ -88,6 +210,7 @@ fn check_node_references(
                 check_unresolved_references(
                     diag,
                     nodes,
+                    unloaded_elements,
                     &node.in_context_of,
                     id,
                     &node.module,
@@ -96,12 +219,20 @@ fn check_node_references(
                 check_unresolved_references(
                     diag,
                     nodes,
+                    unloaded_elements,
                     &node.supported_by,
                     id,
                     &node.module,
                     "supported by",
                 ),
-                check_challenges(diag, nodes, &node.challenges, id, &node.module),
+                check_challenges(
+                    diag,
+                    nodes,
+                    unloaded_elements,
+                    &node.challenges,
+                    id,
+                    &node.module,
+                ),
             ]
         })
         .collect::<Result<(), ()>>()
@@ -114,6 +245,7 @@ fn check_node_references(
 fn check_unresolved_references(
     diag: &mut Diagnostics,
     nodes: &BTreeMap<String, GsnNode>,
+    unloaded_elements: &BTreeMap<String, String>,
     in_refs: &[String],
     id: &str,
     module: &str,
@@ -123,13 +255,22 @@ fn check_unresolved_references(
         .iter()
         .filter(|&n| !nodes.contains_key(n))
         .try_for_each(|wref| {
-            diag.add_error(
+            let location = nodes.get(id).and_then(|n| n.get_reference_location(wref));
+            let hint = get_unresolved_hint(nodes, unloaded_elements, module, wref);
+            diag.add_error_at(
                 Some(module),
-                format!("C03: Element {id} has unresolved \"{error_str}\" element: {wref}"),
+                location,
+                &[id, wref.as_str()],
+                format!(
+                    "C03: Element {id} has unresolved \"{error_str}\" element: {wref}{}{hint}",
+                    if hint.is_empty() { "" } else { "." }
+                ),
             );
             if wref.contains(',') {
-                diag.add_warning(
+                diag.add_warning_at(
                     Some(module),
+                    location,
+                    &[id, wref.as_str()],
                     format!(
                         "C11: Unresolved \"{error_str}\" element of {id} may be actually a list: {wref}. Try writing [{wref}] instead."
                     ),
@@ -139,28 +280,106 @@ fn check_unresolved_references(
         })
 }
 
+///
+/// Get a hint for an unresolved reference to `target` from an element in `module`.
+///
+/// If the element exists in a file that is not loaded, the missing `uses` entry is named.
+/// Otherwise, similar IDs of existing elements are suggested.
+///
+fn get_unresolved_hint(
+    nodes: &BTreeMap<String, GsnNode>,
+    unloaded_elements: &BTreeMap<String, String>,
+    module: &str,
+    target: &str,
+) -> String {
+    if let Some(file) = unloaded_elements.get(target) {
+        format!(
+            " Element {target} exists in {file}, but that file is not used. Add it to `uses` of module {module}."
+        )
+    } else {
+        let similar = find_similar_ids(target, nodes.keys());
+        if similar.is_empty() {
+            String::new()
+        } else {
+            format!(" Did you mean {}?", similar.join(", "))
+        }
+    }
+}
+
+///
+/// Maximum number of cycles that are reported for a group of elements connected by cycles.
+///
+const MAX_CYCLES: usize = 100;
+
 ///
 /// Check for cycles in `supported by` references
-/// It also detects if there is a cycle in an independent graph.
+/// It also detects cycles in independent graphs.
 ///
+/// Every elementary cycle is reported with its path and the modules of its elements.
+/// If the elements of a strongly connected component form more than [MAX_CYCLES] cycles,
+/// only the first ones are reported, followed by a message listing all elements of the component.
 ///
 fn check_cycles<'a>(
     diag: &mut Diagnostics,
     graph: &'a DirectedGraph<GsnNode, GsnEdgeType<'a>>,
 ) -> Result<(), ()> {
-    let cycle = graph.get_first_cycle();
-    if let Some((found, ring)) = cycle {
-        diag.add_error(
-            None,
-            format!(
-                "C04: Cycle detected at element {}. Cycle is {}.",
-                found,
-                &ring.join(" -> "),
-            ),
-        );
-        Err(())
-    } else {
+    let components = graph.get_cycles(MAX_CYCLES);
+    let module_of = |id: &str| {
+        graph
+            .get_nodes()
+            .get(id)
+            .map(|n| n.module.as_str())
+            .unwrap_or_default()
+    };
+    for component in &components {
+        for cycle in &component.cycles {
+            // The element that closes the cycle
+            let found = cycle[cycle.len() - 2];
+            let mut modules: Vec<&str> = cycle
+                .iter()
+                .map(|&n| module_of(n))
+                .filter(|m| !m.is_empty())
+                .collect();
+            modules.sort();
+            modules.dedup();
+            let path = if modules.len() > 1 {
+                cycle
+                    .iter()
+                    .map(|&n| format!("{n} ({})", module_of(n)))
+                    .collect::<Vec<_>>()
+                    .join(" -> ")
+            } else {
+                cycle.join(" -> ")
+            };
+            let membership = match modules.as_slice() {
+                [module] => format!(" in module {module}"),
+                _ => String::new(),
+            };
+            diag.add_error_at(
+                None,
+                graph.get_nodes().get(found).and_then(|n| n.get_location()),
+                cycle,
+                format!("C04: Cycle detected at element {found}. Cycle is {path}{membership}."),
+            );
+        }
+        if component.truncated {
+            // unwrap is ok, since a component is not empty
+            let first = component.elements.first().unwrap();
+            diag.add_error_at(
+                None,
+                graph.get_nodes().get(*first).and_then(|n| n.get_location()),
+                &component.elements,
+                format!(
+                    "C04: More than {MAX_CYCLES} cycles detected between the elements {}. Only the first {MAX_CYCLES} are reported.",
+                    component.elements.join(", ")
+                ),
+            );
+        }
+    }
+    if components.is_empty() {
         Ok(())
+    } else {
+        Err(())
     }
 }
 
@@ -190,8 +409,13 @@ fn check_unreachable<'a>(
     if unvisited.is_empty() {
         Ok(())
     } else {
-        diag.add_error(
+        diag.add_error_at(
             None,
+            unvisited
+                .first()
+                .and_then(|&n| graph.get_nodes().get(n))
+                .and_then(|n| n.get_location()),
+            &unvisited,
             format!(
                 "C08: The following element(s) are not reachable from the root element(s) ({}): {}",
                 root_nodes.join(", "),
@@ -202,6 +426,59 @@ fn check_unreachable<'a>(
     }
 }
 
+///
+/// Check that elements are only referenced from other modules if they are public.
+/// Modules that neither mark elements as `public` nor declare an `interface`
+/// keep all their elements public.
+///
+pub fn check_module_interfaces(
+    diag: &mut Diagnostics,
+    nodes: &BTreeMap<String, GsnNode>,
+    modules: &BTreeMap<String, Module>,
+    excluded_modules: &[&str],
+) -> Result<(), ()> {
+    let mut errors = 0;
+    let restricted_modules = get_restricted_modules(nodes);
+    for (module_name, module) in modules {
+        for id in &module.meta.interface {
+            if nodes.get(id).is_none_or(|n| &n.module != module_name) {
+                diag.add_error_at(
+                    Some(module_name),
+                    module.location.as_ref(),
+                    &[id.as_str()],
+                    format!(
+                        "C19: Element {id} is part of the interface of module {module_name}, but does not exist in that module."
+                    ),
+                );
+                errors += 1;
+            }
+        }
+    }
+    for (id, node) in nodes
+        .iter()
+        .filter(|(_, n)| !excluded_modules.contains(&n.module.as_str()))
+    {
+        for (target, _) in node.get_edges() {
+            if let Some(target_node) = nodes.get(&target)
+                && target_node.module != node.module
+                && !is_public(nodes, &restricted_modules, &target)
+            {
+                diag.add_error_at(
+                    Some(&node.module),
+                    node.get_reference_location(&target),
+                    &[id.as_str(), target.as_str()],
+                    format!(
+                        "C19: Element {id} references element {target} of module {}, but it is not public.",
+                        target_node.module
+                    ),
+                );
+                errors += 1;
+            }
+        }
+    }
+    if errors == 0 { Ok(()) } else { Err(()) }
+}
+
 ///
 /// Checks if the layers handed in via command line parameters
 /// are actually used at at least one node.
@@ -212,26 +489,10 @@ pub fn check_layers(
     nodes: &BTreeMap<String, GsnNode>,
     layers: &[&str],
 ) -> Result<(), ()> {
-    let reserved_words = [
-        "text",
-        "inContextOf",
-        "supportedBy",
-        "challenges",
-        "defeated",
-        "defeatedRelation",
-        "classes",
-        "url",
-        "undeveloped",
-        "nodeType",
-        "rankIncrement",
-        "horizontalIndex",
-        "charWrap",
-        "acp",
-    ];
     let layer_results = layers
         .iter()
         .map(|l| {
-            if reserved_words.contains(l) {
+            if ELEMENT_ATTRIBUTES.contains(l) {
                 diag.add_error(
                     None,
                     format!("{l} is a reserved attribute and cannot be used as layer."),
@@ -267,6 +528,7 @@ pub fn check_layers(
 fn check_challenges(
     diag: &mut Diagnostics,
     nodes: &BTreeMap<String, GsnNode>,
+    unloaded_elements: &BTreeMap<String, String>,
     challenges: &Option<Challenge>,
     id: &str,
     module: &str,
@@ -290,19 +552,27 @@ fn check_challenges(
         rels
     }
 
+    let location = |target: &str| nodes.get(id).and_then(|n| n.get_reference_location(target));
     if let Some(c) = challenges {
         match c {
             Challenge::Node(n) => {
                 if n == id {
-                    diag.add_error(
+                    diag.add_error_at(
                         Some(module),
+                        location(n),
+                        &[id],
                         format!("C13: Element {id} challenges itself."),
                     );
                     Err(())
                 } else if !nodes.contains_key(n) {
-                    diag.add_error(
+                    diag.add_error_at(
                         Some(module),
-                        format!("C14: Element {id} challenges element {n}, but it does not exist."),
+                        location(n),
+                        &[id, n.as_str()],
+                        format!(
+                            "C14: Element {id} challenges element {n}, but it does not exist.{}",
+                            get_unresolved_hint(nodes, unloaded_elements, module, n)
+                        ),
                     );
                     Err(())
                 } else {
@@ -311,30 +581,44 @@ fn check_challenges(
             }
             Challenge::Relation((l, r)) => {
                 if l == r {
-                    diag.add_error(
+                    diag.add_error_at(
                         Some(module),
+                        location(l),
+                        &[id, l.as_str()],
                         format!(
                             "C15: Element {id} challenges a relation with both ends pointing to {l}."
                         ),
                     );
                     Err(())
                 } else if !nodes.contains_key(l) {
-                    diag.add_error(
+                    diag.add_error_at(
                         Some(module),
-                        format!("C12: Element {id} challenges a relation, but element {l} of the relation does not exist."),
+                        location(l),
+                        &[id, l.as_str()],
+                        format!(
+                            "C12: Element {id} challenges a relation, but element {l} of the relation does not exist.{}",
+                            get_unresolved_hint(nodes, unloaded_elements, module, l)
+                        ),
                     );
                     Err(())
                 } else if !nodes.contains_key(r) {
-                    diag.add_error(
+                    diag.add_error_at(
                         Some(module),
-                        format!("C12: Element {id} challenges a relation, but element {r} of the relation does not exist."),
+                        location(r),
+                        &[id, r.as_str()],
+                        format!(
+                            "C12: Element {id} challenges a relation, but element {r} of the relation does not exist.{}",
+                            get_unresolved_hint(nodes, unloaded_elements, module, r)
+                        ),
                     );
                     Err(())
                 } else if !(get_relations(nodes, r).contains(&l)
                     || get_relations(nodes, l).contains(&r))
                 {
-                    diag.add_error(
+                    diag.add_error_at(
                         Some(module),
+                        location(l),
+                        &[id, l.as_str(), r.as_str()],
                         format!("C16: Element {id} challenges a relation, but the referenced elements {r} and {l} do not have a relation."),
                     );
                     Err(())
@@ -368,7 +652,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(check_nodes(&mut d, &nodes, &[]).is_err());
+        assert!(check_nodes(&mut d, &nodes, &[], &BTreeMap::new(), &BTreeMap::new()).is_err());
         assert_eq!(d.messages.len(), 1);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -392,7 +676,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(check_nodes(&mut d, &nodes, &[]).is_err());
+        assert!(check_nodes(&mut d, &nodes, &[], &BTreeMap::new(), &BTreeMap::new()).is_err());
         assert_eq!(d.messages.len(), 1);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -439,7 +723,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(check_nodes(&mut d, &nodes, &[]).is_err());
+        assert!(check_nodes(&mut d, &nodes, &[], &BTreeMap::new(), &BTreeMap::new()).is_err());
         assert_eq!(d.messages.len(), 1);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -463,7 +747,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(check_nodes(&mut d, &nodes, &[]).is_err());
+        assert!(check_nodes(&mut d, &nodes, &[], &BTreeMap::new(), &BTreeMap::new()).is_err());
         assert_eq!(d.messages.len(), 2);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -493,7 +777,7 @@ mod test {
             },
         );
         nodes.insert("C1".to_owned(), GsnNode::default());
-        assert!(check_nodes(&mut d, &nodes, &[]).is_ok());
+        assert!(check_nodes(&mut d, &nodes, &[], &BTreeMap::new(), &BTreeMap::new()).is_ok());
         assert_eq!(d.messages.len(), 1);
         assert_eq!(d.messages[0].module, None);
         assert_eq!(d.messages[0].diag_type, DiagType::Warning);
@@ -516,7 +800,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(check_nodes(&mut d, &nodes, &[]).is_err());
+        assert!(check_nodes(&mut d, &nodes, &[], &BTreeMap::new(), &BTreeMap::new()).is_err());
         assert_eq!(d.messages.len(), 2);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -534,6 +818,62 @@ mod test {
         assert_eq!(d.warnings, 1);
     }
 
+    #[test]
+    fn unresolved_similar_id() {
+        let mut d = Diagnostics::default();
+        let mut nodes = BTreeMap::<String, GsnNode>::new();
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                supported_by: vec!["Sn10".to_owned()],
+                ..Default::default()
+            },
+        );
+        nodes.insert("Sn1".to_owned(), GsnNode::default());
+        assert!(check_nodes(&mut d, &nodes, &[], &BTreeMap::new(), &BTreeMap::new()).is_err());
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(
+            d.messages[0].msg,
+            "C03: Element G1 has unresolved \"supported by\" element: Sn10. Did you mean Sn1?"
+        );
+        let mut d = Diagnostics::default();
+        nodes.get_mut("G1").unwrap().supported_by = vec!["Sn1".to_owned()];
+        nodes.insert(
+            "CG1".to_owned(),
+            GsnNode {
+                challenges: Some(Challenge::Node("sn1".to_owned())),
+                ..Default::default()
+            },
+        );
+        assert!(check_nodes(&mut d, &nodes, &[], &BTreeMap::new(), &BTreeMap::new()).is_err());
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(
+            d.messages[0].msg,
+            "C14: Element CG1 challenges element sn1, but it does not exist. Did you mean Sn1?"
+        );
+    }
+
+    #[test]
+    fn unresolved_unloaded_element() {
+        let mut d = Diagnostics::default();
+        let mut nodes = BTreeMap::<String, GsnNode>::new();
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                supported_by: vec!["S1".to_owned()],
+                module: "main".to_owned(),
+                ..Default::default()
+            },
+        );
+        let unloaded_elements = BTreeMap::from([("S1".to_owned(), "sub.yaml".to_owned())]);
+        assert!(check_nodes(&mut d, &nodes, &[], &BTreeMap::new(), &unloaded_elements).is_err());
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(
+            d.messages[0].msg,
+            "C03: Element G1 has unresolved \"supported by\" element: S1. Element S1 exists in sub.yaml, but that file is not used. Add it to `uses` of module main."
+        );
+    }
+
     #[test]
     fn challenges_self() {
         let mut d = Diagnostics::default();
@@ -545,7 +885,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(check_nodes(&mut d, &nodes, &[]).is_err());
+        assert!(check_nodes(&mut d, &nodes, &[], &BTreeMap::new(), &BTreeMap::new()).is_err());
         assert_eq!(d.messages.len(), 1);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -586,7 +926,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(check_nodes(&mut d, &nodes, &[]).is_err());
+        assert!(check_nodes(&mut d, &nodes, &[], &BTreeMap::new(), &BTreeMap::new()).is_err());
         assert_eq!(d.messages.len(), 1);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -616,7 +956,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(check_nodes(&mut d, &nodes, &[]).is_err());
+        assert!(check_nodes(&mut d, &nodes, &[], &BTreeMap::new(), &BTreeMap::new()).is_err());
         assert_eq!(d.messages.len(), 1);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -646,7 +986,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(check_nodes(&mut d, &nodes, &[]).is_err());
+        assert!(check_nodes(&mut d, &nodes, &[], &BTreeMap::new(), &BTreeMap::new()).is_err());
         assert_eq!(d.messages.len(), 1);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -689,7 +1029,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(check_nodes(&mut d, &nodes, &[]).is_err());
+        assert!(check_nodes(&mut d, &nodes, &[], &BTreeMap::new(), &BTreeMap::new()).is_err());
         assert_eq!(d.messages.len(), 1);
         // assert_eq!(d.messages[0].module, None);
         // assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -726,7 +1066,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(check_nodes(&mut d, &nodes, &[]).is_err());
+        assert!(check_nodes(&mut d, &nodes, &[], &BTreeMap::new(), &BTreeMap::new()).is_err());
         assert_eq!(d.messages.len(), 1);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -780,6 +1120,103 @@ mod test {
         assert_eq!(d.warnings, 0);
     }
 
+    #[test]
+    fn several_cycles() {
+        let mut d = Diagnostics::default();
+        let mut nodes = BTreeMap::<String, GsnNode>::new();
+        for (id, module, supported_by) in [
+            ("G0", "main", vec!["G1", "G4"]),
+            ("G1", "main", vec!["G2"]),
+            ("G2", "main", vec!["G1", "G3"]),
+            ("G3", "main", vec!["G1"]),
+            ("G4", "sub", vec!["G5"]),
+            ("G5", "main", vec!["G4"]),
+        ] {
+            nodes.insert(
+                id.to_owned(),
+                GsnNode {
+                    supported_by: supported_by.into_iter().map(str::to_owned).collect(),
+                    module: module.to_owned(),
+                    ..Default::default()
+                },
+            );
+        }
+        let edges: BTreeMap<String, Vec<(String, GsnEdgeType)>> = nodes
+            .iter()
+            .map(|(id, node)| (id.to_owned(), node.get_edges()))
+            .collect();
+        let graph = DirectedGraph::new(&nodes, &edges);
+        assert!(check_cycles(&mut d, &graph).is_err());
+        assert_eq!(
+            d.messages
+                .iter()
+                .map(|m| m.msg.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "C04: Cycle detected at element G2. Cycle is G1 -> G2 -> G1 in module main.",
+                "C04: Cycle detected at element G3. Cycle is G1 -> G2 -> G3 -> G1 in module main.",
+                "C04: Cycle detected at element G5. Cycle is G4 (sub) -> G5 (main) -> G4 (sub).",
+            ]
+        );
+        assert_eq!(d.messages[2].elements, vec!["G4", "G5", "G4"]);
+        assert_eq!(d.errors, 3);
+    }
+
+    fn complete_graph(size: usize) -> BTreeMap<String, GsnNode> {
+        let ids: Vec<String> = (1..=size).map(|i| format!("G{i}")).collect();
+        ids.iter()
+            .map(|id| {
+                (
+                    id.to_owned(),
+                    GsnNode {
+                        supported_by: ids.iter().filter(|&o| o != id).cloned().collect(),
+                        module: "main".to_owned(),
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn all_elementary_cycles() {
+        let mut d = Diagnostics::default();
+        let nodes = complete_graph(4);
+        let edges: BTreeMap<String, Vec<(String, GsnEdgeType)>> = nodes
+            .iter()
+            .map(|(id, node)| (id.to_owned(), node.get_edges()))
+            .collect();
+        let graph = DirectedGraph::new(&nodes, &edges);
+        assert!(check_cycles(&mut d, &graph).is_err());
+        // 6 cycles of length 2, 8 of length 3 and 6 of length 4
+        assert_eq!(d.errors, 20);
+        assert!(d.messages.iter().any(|m| {
+            m.msg
+                .ends_with("Cycle is G1 -> G3 -> G2 -> G4 -> G1 in module main.")
+        }));
+    }
+
+    #[test]
+    fn too_many_cycles() {
+        let mut d = Diagnostics::default();
+        let nodes = complete_graph(6);
+        let edges: BTreeMap<String, Vec<(String, GsnEdgeType)>> = nodes
+            .iter()
+            .map(|(id, node)| (id.to_owned(), node.get_edges()))
+            .collect();
+        let graph = DirectedGraph::new(&nodes, &edges);
+        assert!(check_cycles(&mut d, &graph).is_err());
+        assert_eq!(d.errors, MAX_CYCLES + 1);
+        assert_eq!(
+            d.messages.last().unwrap().msg,
+            "C04: More than 100 cycles detected between the elements G1, G2, G3, G4, G5, G6. Only the first 100 are reported."
+        );
+        assert_eq!(
+            d.messages.last().unwrap().elements,
+            vec!["G1", "G2", "G3", "G4", "G5", "G6"]
+        );
+    }
+
     #[test]
     fn simple_cycle_2() {
         let mut d = Diagnostics::default();
@@ -805,7 +1242,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(check_nodes(&mut d, &nodes, &[]).is_err());
+        assert!(check_nodes(&mut d, &nodes, &[], &BTreeMap::new(), &BTreeMap::new()).is_err());
         assert_eq!(d.messages.len(), 1);
         assert_eq!(d.messages[0].module, None);
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -853,7 +1290,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(check_nodes(&mut d, &nodes, &[]).is_ok());
+        assert!(check_nodes(&mut d, &nodes, &[], &BTreeMap::new(), &BTreeMap::new()).is_ok());
         assert_eq!(d.messages.len(), 0);
         assert_eq!(d.errors, 0);
         assert_eq!(d.warnings, 0);
@@ -926,13 +1363,13 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(check_nodes(&mut d, &nodes, &[]).is_err());
+        assert!(check_nodes(&mut d, &nodes, &[], &BTreeMap::new(), &BTreeMap::new()).is_err());
         assert_eq!(d.messages.len(), 1);
         assert_eq!(d.messages[0].module, None);
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
         assert_eq!(
             d.messages[0].msg,
-            "C08: The following element(s) are not reachable from the root element(s) (G3): G1, G2, S1"
+            "C04: Cycle detected at element G2. Cycle is G1 -> S1 -> G2 -> G1."
         );
         assert_eq!(d.errors, 1);
         assert_eq!(d.warnings, 0);
@@ -949,7 +1386,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(check_nodes(&mut d, &nodes, &[]).is_err());
+        assert!(check_nodes(&mut d, &nodes, &[], &BTreeMap::new(), &BTreeMap::new()).is_err());
         assert_eq!(d.messages.len(), 1);
         assert_eq!(d.messages[0].module, None);
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -1061,11 +1498,214 @@ mod test {
         assert_eq!(d.warnings, 1);
     }
 
+    fn interface_nodes() -> BTreeMap<String, GsnNode> {
+        let mut nodes = BTreeMap::<String, GsnNode>::new();
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                supported_by: vec!["G2".to_owned()],
+                in_context_of: vec!["C1".to_owned()],
+                module: "main".to_owned(),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "G2".to_owned(),
+            GsnNode {
+                undeveloped: true,
+                public: true,
+                module: "sub".to_owned(),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "C1".to_owned(),
+            GsnNode {
+                module: "sub".to_owned(),
+                ..Default::default()
+            },
+        );
+        nodes
+    }
+
+    #[test]
+    fn reference_private_element() {
+        let mut d = Diagnostics::default();
+        let nodes = interface_nodes();
+        let res = check_module_interfaces(&mut d, &nodes, &BTreeMap::new(), &[]);
+        assert!(res.is_err());
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(d.messages[0].module, Some("main".to_owned()));
+        assert_eq!(d.messages[0].diag_type, DiagType::Error);
+        assert_eq!(
+            d.messages[0].msg,
+            "C19: Element G1 references element C1 of module sub, but it is not public."
+        );
+        assert_eq!(d.errors, 1);
+        assert_eq!(d.warnings, 0);
+    }
+
+    #[test]
+    fn reference_private_element_excluded() {
+        let mut d = Diagnostics::default();
+        let nodes = interface_nodes();
+        let res = check_module_interfaces(&mut d, &nodes, &BTreeMap::new(), &["main"]);
+        assert!(res.is_ok());
+        assert_eq!(d.messages.len(), 0);
+    }
+
+    #[test]
+    fn no_interface_all_public() {
+        let mut d = Diagnostics::default();
+        let mut nodes = interface_nodes();
+        nodes.get_mut("G2").unwrap().public = false;
+        let res = check_module_interfaces(&mut d, &nodes, &BTreeMap::new(), &[]);
+        assert!(res.is_ok());
+        assert_eq!(d.messages.len(), 0);
+    }
+
+    #[test]
+    fn unknown_interface_element() {
+        let mut d = Diagnostics::default();
+        let mut nodes = interface_nodes();
+        nodes.get_mut("C1").unwrap().public = true;
+        let mut meta = crate::gsn::ModuleInformation::new("main".to_owned());
+        meta.interface = vec!["G2".to_owned()];
+        let modules = BTreeMap::from([(
+            "main".to_owned(),
+            Module {
+                meta,
+                ..Default::default()
+            },
+        )]);
+        let res = check_module_interfaces(&mut d, &nodes, &modules, &[]);
+        assert!(res.is_err());
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(d.messages[0].module, Some("main".to_owned()));
+        assert_eq!(
+            d.messages[0].msg,
+            "C19: Element G2 is part of the interface of module main, but does not exist in that module."
+        );
+        assert_eq!(d.errors, 1);
+    }
+
+    #[test]
+    fn declared_roots() {
+        let mut d = Diagnostics::default();
+        let mut nodes = BTreeMap::<String, GsnNode>::new();
+        for id in ["G1", "G2", "G3"] {
+            nodes.insert(
+                id.to_owned(),
+                GsnNode {
+                    node_type: Some(GsnNodeType::Goal),
+                    module: "main".to_owned(),
+                    undeveloped: true,
+                    ..Default::default()
+                },
+            );
+        }
+        let mut meta = crate::gsn::ModuleInformation::new("main".to_owned());
+        meta.roots = vec!["G1".to_owned(), "G2".to_owned()];
+        let mut modules = BTreeMap::from([(
+            "main".to_owned(),
+            Module {
+                meta,
+                ..Default::default()
+            },
+        )]);
+        assert!(check_root_nodes(&mut d, &nodes, &modules).is_ok());
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(d.messages[0].module, Some("main".to_owned()));
+        assert_eq!(
+            d.messages[0].msg,
+            "C01: Element G3 is unreferenced, but not declared in the roots of module main."
+        );
+        assert_eq!(d.warnings, 1);
+
+        let mut d = Diagnostics::default();
+        nodes.get_mut("G1").unwrap().supported_by = vec!["G2".to_owned()];
+        modules
+            .get_mut("main")
+            .unwrap()
+            .meta
+            .roots
+            .push("G4".to_owned());
+        assert!(check_root_nodes(&mut d, &nodes, &modules).is_err());
+        assert_eq!(
+            d.messages
+                .iter()
+                .map(|m| m.msg.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "C01: Element G2 is declared as root of module main, but is referenced by G1.",
+                "C01: Element G4 is declared as root of module main, but does not exist in that module.",
+                "C01: Element G3 is unreferenced, but not declared in the roots of module main.",
+            ]
+        );
+        assert_eq!(d.errors, 2);
+    }
+
+    #[test]
+    fn declared_roots_of_used_module() {
+        let goal = |module: &str, supported_by: &[&str]| GsnNode {
+            node_type: Some(GsnNodeType::Goal),
+            module: module.to_owned(),
+            supported_by: supported_by.iter().map(|s| s.to_string()).collect(),
+            undeveloped: supported_by.is_empty(),
+            ..Default::default()
+        };
+        let nodes = BTreeMap::from([
+            ("G1".to_owned(), goal("main", &["G2"])),
+            ("G2".to_owned(), goal("lib", &[])),
+            ("G3".to_owned(), goal("lib", &[])),
+        ]);
+        let mut meta = crate::gsn::ModuleInformation::new("lib".to_owned());
+        meta.roots = vec!["G2".to_owned(), "G3".to_owned()];
+        let modules = BTreeMap::from([
+            (
+                "main".to_owned(),
+                Module {
+                    meta: crate::gsn::ModuleInformation::new("main".to_owned()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "lib".to_owned(),
+                Module {
+                    meta,
+                    ..Default::default()
+                },
+            ),
+        ]);
+        let mut d = Diagnostics::default();
+        assert_eq!(
+            check_root_nodes(&mut d, &nodes, &modules),
+            Ok(vec!["G2".to_owned(), "G3".to_owned(), "G1".to_owned()])
+        );
+        assert_eq!(d.messages.len(), 0);
+
+        let mut nodes = nodes;
+        nodes.insert("G4".to_owned(), goal("main", &[]));
+        nodes.insert("G5".to_owned(), goal("lib", &[]));
+        let mut d = Diagnostics::default();
+        assert!(check_root_nodes(&mut d, &nodes, &modules).is_ok());
+        assert_eq!(
+            d.messages
+                .iter()
+                .map(|m| m.msg.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "C01: Element G5 is unreferenced, but not declared in the roots of module lib.",
+                "C01: There is more than one unreferenced element: G1, G4.",
+            ]
+        );
+    }
+
     #[test]
     fn empty_document() {
         let mut d = Diagnostics::default();
         let nodes = BTreeMap::<String, GsnNode>::new();
-        assert!(check_root_nodes(&mut d, &nodes).is_ok());
+        assert!(check_root_nodes(&mut d, &nodes, &BTreeMap::new()).is_ok());
         assert_eq!(d.messages.len(), 0);
     }
 }
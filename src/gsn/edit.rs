@@ -0,0 +1,804 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+///
+/// An input file as a sequence of lines, split into top-level entries
+/// (elements and module information) and their attributes.
+///
+/// Comments and blank lines are kept. Rendering an unmodified source yields the original text.
+/// Only block mappings are split into attributes, which is the layout used by all examples.
+///
+#[derive(Debug)]
+pub struct SourceFile {
+    pub entries: Vec<Entry>,
+    /// Comments and blank lines after the last entry
+    pub trailer: Vec<String>,
+    line_ending: &'static str,
+    pub final_newline: bool,
+}
+
+///
+/// A top-level entry, i.e. an element or the module information.
+///
+#[derive(Debug)]
+pub struct Entry {
+    /// Comments, blank lines and other lines (like document markers) before the entry
+    pub leading: Vec<String>,
+    pub key: String,
+    /// The line of the key itself
+    pub line: String,
+    pub attributes: Vec<Attribute>,
+}
+
+///
+/// An attribute of an entry with all its lines.
+///
+#[derive(Debug)]
+pub struct Attribute {
+    /// Comments and blank lines before the attribute
+    pub leading: Vec<String>,
+    pub key: String,
+    pub indent: usize,
+    /// The line of the key and all continuation lines
+    pub lines: Vec<String>,
+}
+
+///
+/// The style in which a list of references is written.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ListStyle {
+    /// A single string, e.g. `supportedBy: G2`
+    Scalar,
+    /// A flow sequence, e.g. `supportedBy: [G2, G3]`
+    Flow,
+    /// A block sequence with one `- G2` line per reference
+    Block,
+}
+
+impl SourceFile {
+    ///
+    /// Split `content` into entries and attributes.
+    ///
+    pub fn parse(content: &str) -> SourceFile {
+        let mut entries: Vec<Entry> = vec![];
+        let mut pending = vec![];
+        let mut attribute_indent = None;
+        for line in content.lines() {
+            let trimmed = line.trim_start();
+            let indent = line.len() - trimmed.len();
+            if trimmed.is_empty() || entries.is_empty() && indent > 0 {
+                pending.push(line.to_owned());
+            } else if indent == 0 {
+                match parse_key(trimmed) {
+                    Some(key) => {
+                        entries.push(Entry {
+                            leading: std::mem::take(&mut pending),
+                            key,
+                            line: line.to_owned(),
+                            attributes: vec![],
+                        });
+                        attribute_indent = None;
+                    }
+                    // Comments and document markers
+                    None => pending.push(line.to_owned()),
+                }
+            } else if trimmed.starts_with('#') && attribute_indent.is_none_or(|i| indent <= i) {
+                pending.push(line.to_owned());
+            } else {
+                // unwrap ok, since entries is not empty here
+                let entry = entries.last_mut().unwrap();
+                let attribute_indent = *attribute_indent.get_or_insert(indent);
+                match parse_key(trimmed).filter(|_| indent == attribute_indent) {
+                    Some(key) => entry.attributes.push(Attribute {
+                        leading: std::mem::take(&mut pending),
+                        key,
+                        indent,
+                        lines: vec![line.to_owned()],
+                    }),
+                    None => match entry.attributes.last_mut() {
+                        Some(attribute) => {
+                            attribute.lines.append(&mut pending);
+                            attribute.lines.push(line.to_owned());
+                        }
+                        // Not a block mapping, keep the lines as they are
+                        None => entry.attributes.push(Attribute {
+                            leading: std::mem::take(&mut pending),
+                            key: String::new(),
+                            indent,
+                            lines: vec![line.to_owned()],
+                        }),
+                    },
+                }
+            }
+        }
+        SourceFile {
+            entries,
+            trailer: pending,
+            line_ending: if content.contains("\r\n") {
+                "\r\n"
+            } else {
+                "\n"
+            },
+            final_newline: content.ends_with('\n'),
+        }
+    }
+
+    ///
+    /// Get the entry with key `key` for modification.
+    ///
+    pub fn entry_mut(&mut self, key: &str) -> Option<&mut Entry> {
+        self.entries.iter_mut().find(|e| e.key == key)
+    }
+}
+
+impl Display for SourceFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines = self
+            .entries
+            .iter()
+            .flat_map(|e| {
+                e.leading.iter().chain(std::iter::once(&e.line)).chain(
+                    e.attributes
+                        .iter()
+                        .flat_map(|a| a.leading.iter().chain(&a.lines)),
+                )
+            })
+            .chain(&self.trailer)
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        f.write_str(&lines.join(self.line_ending))?;
+        if self.final_newline && !lines.is_empty() {
+            f.write_str(self.line_ending)?;
+        }
+        Ok(())
+    }
+}
+
+impl Entry {
+    ///
+    /// Get the attribute `key` for modification.
+    ///
+    pub fn attribute_mut(&mut self, key: &str) -> Option<&mut Attribute> {
+        self.attributes.iter_mut().find(|a| a.key == key)
+    }
+
+    ///
+    /// Check if the entry is a block mapping, i.e. its attributes can be edited.
+    ///
+    pub fn is_block_mapping(&self) -> bool {
+        value_of(&self.line).is_empty()
+            && self
+                .attributes
+                .iter()
+                .all(|a| !a.key.is_empty() || a.lines.is_empty())
+    }
+
+    ///
+    /// Set attribute `key` to the single line `value`.
+    /// A missing attribute is appended to the entry.
+    /// Returns false, if the entry is not a block mapping.
+    ///
+    pub fn set_attribute(&mut self, key: &str, value: &str) -> bool {
+        if !self.is_block_mapping() {
+            return false;
+        }
+        let indent = self.attributes.first().map(|a| a.indent).unwrap_or(2);
+        match self.attribute_mut(key) {
+            Some(attribute) => attribute.set_value(value),
+            None => self.attributes.push(Attribute {
+                leading: vec![],
+                key: key.to_owned(),
+                indent,
+                lines: vec![format!("{}{key}: {value}", " ".repeat(indent))],
+            }),
+        }
+        true
+    }
+
+    ///
+    /// Rename the entry to `key`.
+    ///
+    pub fn rename(&mut self, key: &str) {
+        let rest = key_end(&self.line)
+            .map(|end| self.line[end - 1..].to_owned())
+            .unwrap_or_else(|| ":".to_owned());
+        self.line = format!("{}{rest}", yaml_string(key));
+        self.key = key.to_owned();
+    }
+}
+
+impl Attribute {
+    ///
+    /// Get the line of the key up to and including the colon and a space.
+    ///
+    fn key_prefix(&self) -> String {
+        let first = self.lines.first().map(String::as_str).unwrap_or_default();
+        let end = key_end(first).unwrap_or(first.len());
+        format!("{}: ", &first[..end - 1])
+    }
+
+    ///
+    /// Get the value on the line of the key without comment.
+    ///
+    pub fn value(&self) -> &str {
+        self.lines.first().map(|l| value_of(l)).unwrap_or_default()
+    }
+
+    ///
+    /// Get all comments of the attribute (incl. the leading `#`).
+    ///
+    pub fn comments(&self) -> Vec<String> {
+        self.lines
+            .iter()
+            .enumerate()
+            .filter_map(|(i, l)| {
+                let content = if i == 0 {
+                    &l[key_end(l).unwrap_or(0)..]
+                } else {
+                    l.as_str()
+                };
+                split_comment(content).1.map(str::to_owned)
+            })
+            .collect()
+    }
+
+    ///
+    /// Replace all scalars in the value of this attribute (incl. nested keys) according to `replacements`.
+    /// Returns the number of replacements.
+    ///
+    pub fn replace_scalars(&mut self, replacements: &BTreeMap<&str, &str>) -> usize {
+        let mut count = 0;
+        for (i, line) in self.lines.iter_mut().enumerate() {
+            let start = if i == 0 {
+                key_end(line).unwrap_or(0)
+            } else {
+                0
+            };
+            let (content, comment) = split_comment(&line[start..]);
+            let (replaced, n) = replace_tokens(content, replacements);
+            if n > 0 {
+                *line = format!(
+                    "{}{replaced}{}",
+                    &line[..start],
+                    comment.unwrap_or_default()
+                );
+                count += n;
+            }
+        }
+        count
+    }
+
+    ///
+    /// Get the references of this attribute and the style in which they are written.
+    ///
+    pub fn references(&self) -> (ListStyle, Vec<String>) {
+        let value = self.value();
+        if value.starts_with('[') {
+            let flow = std::iter::once(value)
+                .chain(self.lines.iter().skip(1).map(|l| split_comment(l).0.trim()))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let inner = flow.trim_start_matches('[');
+            let inner = inner.rsplit_once(']').map(|(i, _)| i).unwrap_or(inner);
+            (
+                ListStyle::Flow,
+                split_flow(inner)
+                    .into_iter()
+                    .map(|r| unquote(r.trim()))
+                    .filter(|r| !r.is_empty())
+                    .collect(),
+            )
+        } else if !value.is_empty() {
+            (ListStyle::Scalar, vec![unquote(value)])
+        } else {
+            (
+                ListStyle::Block,
+                self.lines
+                    .iter()
+                    .skip(1)
+                    .filter_map(|l| block_item(l))
+                    .collect(),
+            )
+        }
+    }
+
+    ///
+    /// Set the references of this attribute.
+    ///
+    /// Block sequences stay block sequences; unchanged items keep their lines.
+    /// Everything else becomes a flow sequence or stays a single string.
+    /// Comments are kept.
+    ///
+    pub fn set_references(&mut self, references: &[String]) {
+        match self.references().0 {
+            ListStyle::Block => self.set_block_references(references),
+            ListStyle::Scalar if references.len() == 1 => {
+                self.set_value(&yaml_string(&references[0]))
+            }
+            _ => self.set_value(&format_flow_list(references)),
+        }
+    }
+
+    ///
+    /// Set the references of this attribute as a flow sequence.
+    /// Comments are kept.
+    ///
+    pub fn set_flow_references(&mut self, references: &[String]) {
+        self.set_value(&format_flow_list(references));
+    }
+
+    ///
+    /// Replace the value of this attribute with the single line `value`.
+    /// The comment on the line of the key is kept, further comments are moved in front of the attribute.
+    ///
+    pub fn set_value(&mut self, value: &str) {
+        let mut comments = self.comments().into_iter();
+        self.lines = vec![format!(
+            "{}{}",
+            self.key_prefix(),
+            with_comment(value, comments.next())
+        )];
+        self.leading
+            .extend(comments.map(|c| format!("{}{c}", " ".repeat(self.indent))));
+    }
+
+    ///
+    /// Change the indentation of this attribute to `indent`.
+    ///
+    /// The indentation of the attribute is the indentation unit of the file.
+    /// Nested lines are scaled to the new unit. The content of block scalars is shifted instead,
+    /// such that its relative indentation is kept. Leading comments are aligned with the key.
+    ///
+    pub fn reindent(&mut self, indent: usize) {
+        if self.indent == 0 {
+            return;
+        }
+        for line in self.leading.iter_mut().filter(|l| !l.trim().is_empty()) {
+            *line = format!("{}{}", " ".repeat(indent), line.trim_start());
+        }
+        // Old and new indentation of the line that started a block scalar
+        let mut block_scalar: Option<(usize, usize)> = None;
+        for line in self.lines.iter_mut().filter(|l| !l.trim().is_empty()) {
+            let current = line.len() - line.trim_start().len();
+            let new = match block_scalar {
+                Some((old_parent, new_parent)) if current > old_parent => {
+                    current - old_parent + new_parent
+                }
+                _ => {
+                    let new = current / self.indent * indent + current % self.indent;
+                    block_scalar = is_block_scalar_start(line).then_some((current, new));
+                    new
+                }
+            };
+            *line = format!("{}{}", " ".repeat(new), line.trim_start());
+        }
+        self.indent = indent;
+    }
+
+    ///
+    /// Replace the items of a block sequence.
+    /// Items are matched by value first and then by position.
+    ///
+    fn set_block_references(&mut self, references: &[String]) {
+        // Each item with the comments and blank lines before it
+        let mut items: Vec<(Vec<String>, String, String)> = vec![];
+        let mut before = vec![];
+        for line in self.lines.drain(1..) {
+            match block_item(&line) {
+                Some(value) => items.push((std::mem::take(&mut before), value, line)),
+                None => before.push(line),
+            }
+        }
+        let item_indent = items
+            .first()
+            .map(|(_, _, l)| l.len() - l.trim_start().len())
+            .unwrap_or(self.indent + 2);
+        let mut matched: Vec<Option<usize>> = vec![];
+        for reference in references {
+            let found = (0..items.len())
+                .find(|i| items[*i].1 == *reference && !matched.contains(&Some(*i)));
+            matched.push(found);
+        }
+        // Remaining references take the remaining items in order
+        let mut unused = (0..items.len())
+            .filter(|i| !matched.contains(&Some(*i)))
+            .collect::<Vec<_>>()
+            .into_iter();
+        for m in matched.iter_mut().filter(|m| m.is_none()) {
+            *m = unused.next();
+        }
+        let mut items = items.into_iter().map(Some).collect::<Vec<_>>();
+        let mut lines = vec![];
+        for (reference, m) in references.iter().zip(&matched) {
+            let line = match m {
+                Some(i) => {
+                    // Keep the comments of removed items before this one
+                    for (removed, item) in items.iter_mut().enumerate().take(*i) {
+                        if !matched.contains(&Some(removed))
+                            && let Some((mut before, _, line)) = item.take()
+                        {
+                            lines.append(&mut before);
+                            lines.extend(item_comment(&line, item_indent));
+                        }
+                    }
+                    // unwrap ok, since each item is matched at most once
+                    let (mut before, value, line) = items[*i].take().unwrap();
+                    lines.append(&mut before);
+                    if value == *reference {
+                        line
+                    } else {
+                        let dash = line.find('-').unwrap_or(0);
+                        let comment = split_comment(&line[dash + 1..]).1;
+                        format!(
+                            "{}- {}",
+                            &line[..dash],
+                            with_comment(&yaml_string(reference), comment)
+                        )
+                    }
+                }
+                None => format!("{}- {}", " ".repeat(item_indent), yaml_string(reference)),
+            };
+            lines.push(line);
+        }
+        for (mut before, _, line) in items.into_iter().flatten() {
+            lines.append(&mut before);
+            lines.extend(item_comment(&line, item_indent));
+        }
+        lines.append(&mut before);
+        self.lines.append(&mut lines);
+    }
+}
+
+///
+/// Get the comment of a removed block sequence item as a line of its own.
+///
+fn item_comment(line: &str, indent: usize) -> Option<String> {
+    split_comment(&line[line.find('-').unwrap_or(0) + 1..])
+        .1
+        .map(|c| format!("{}{c}", " ".repeat(indent)))
+}
+
+///
+/// Format `references` as a flow sequence.
+///
+pub fn format_flow_list(references: &[String]) -> String {
+    format!(
+        "[{}]",
+        references
+            .iter()
+            .map(|r| yaml_string(r))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+///
+/// Format `value` as a YAML string. Quotes are only added if necessary.
+///
+pub fn yaml_string(value: &str) -> String {
+    let plain = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_alphanumeric() || "_-./ ()".contains(c))
+        && !value.starts_with(['-', ' ', '.'])
+        && !value.ends_with(' ')
+        && value.parse::<f64>().is_err()
+        && !["true", "false", "null", "yes", "no", "on", "off", "~"]
+            .contains(&value.to_lowercase().as_str());
+    if plain {
+        value.to_owned()
+    } else {
+        format!("'{}'", value.replace('\'', "''"))
+    }
+}
+
+///
+/// Append `comment` to `value`, if there is one.
+///
+fn with_comment(value: &str, comment: Option<impl AsRef<str>>) -> String {
+    match comment {
+        Some(comment) => format!("{value} {}", comment.as_ref()),
+        None => value.to_owned(),
+    }
+}
+
+///
+/// Check if `line` ends with the indicator of a block scalar, e.g. `text: |`.
+///
+fn is_block_scalar_start(line: &str) -> bool {
+    let mut tokens = split_comment(line).0.split_whitespace().rev();
+    match (tokens.next(), tokens.next()) {
+        (Some(indicator), Some(before)) => {
+            indicator.starts_with(['|', '>'])
+                && indicator[1..]
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || c == '+' || c == '-')
+                && (before.ends_with(':') || before == "-")
+        }
+        _ => false,
+    }
+}
+
+///
+/// Get the value of a block sequence item line, if it is one.
+///
+fn block_item(line: &str) -> Option<String> {
+    let trimmed = line.trim_start();
+    trimmed
+        .strip_prefix('-')
+        .filter(|rest| rest.is_empty() || rest.starts_with([' ', '\t']))
+        .map(|rest| unquote(split_comment(rest).0.trim()))
+}
+
+///
+/// Get the key of a `key: value` line (without indentation).
+///
+fn parse_key(trimmed: &str) -> Option<String> {
+    if trimmed.starts_with(['#', '[', '{', '%']) || block_item(trimmed).is_some() {
+        return None;
+    }
+    key_end(trimmed).map(|end| unquote(trimmed[..end - 1].trim()))
+}
+
+///
+/// Get the position after the colon that ends the key of `line`.
+///
+fn key_end(line: &str) -> Option<usize> {
+    let mut quote = None;
+    let bytes = line.as_bytes();
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '\'' | '"') if line[..i].trim().is_empty() => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (None, '#') if i == 0 || bytes[i - 1].is_ascii_whitespace() => return None,
+            (None, ':') if bytes.get(i + 1).is_none_or(|b| b.is_ascii_whitespace()) => {
+                return Some(i + 1);
+            }
+            _ => (),
+        }
+    }
+    None
+}
+
+///
+/// Get the value of a `key: value` line without comment.
+///
+fn value_of(line: &str) -> &str {
+    key_end(line)
+        .map(|end| split_comment(&line[end..]).0.trim())
+        .unwrap_or_default()
+}
+
+///
+/// Split `text` into the content and the comment (incl. `#`).
+///
+fn split_comment(text: &str) -> (&str, Option<&str>) {
+    let mut quote = None;
+    let bytes = text.as_bytes();
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (None, '#') if i == 0 || bytes[i - 1].is_ascii_whitespace() => {
+                return (&text[..i], Some(&text[i..]));
+            }
+            _ => (),
+        }
+    }
+    (text, None)
+}
+
+///
+/// Replace all tokens in `text` according to `replacements`.
+/// Tokens are separated by whitespace, flow indicators and colons that end a key.
+///
+fn replace_tokens(text: &str, replacements: &BTreeMap<&str, &str>) -> (String, usize) {
+    let mut result = String::new();
+    let mut count = 0;
+    let mut start = None;
+    let mut flush =
+        |result: &mut String, token: &str| match replacements.get(unquote(token).as_str()) {
+            Some(new) => {
+                result.push_str(&yaml_string(new));
+                count += 1;
+            }
+            None => result.push_str(token),
+        };
+    let bytes = text.as_bytes();
+    for (i, c) in text.char_indices() {
+        let separator = c.is_whitespace()
+            || "[]{},".contains(c)
+            || c == ':' && bytes.get(i + 1).is_none_or(|b| b.is_ascii_whitespace());
+        if separator {
+            if let Some(s) = start.take() {
+                flush(&mut result, &text[s..i]);
+            }
+            result.push(c);
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    if let Some(s) = start {
+        flush(&mut result, &text[s..]);
+    }
+    (result, count)
+}
+
+///
+/// Split the content of a flow sequence at commas outside of quotes.
+///
+fn split_flow(text: &str) -> Vec<&str> {
+    let mut quote = None;
+    let mut start = 0;
+    let mut parts = vec![];
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (None, ',') => {
+                parts.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+///
+/// Remove the quotes of a quoted YAML string.
+///
+fn unquote(value: &str) -> String {
+    if value.len() >= 2 && value.starts_with('\'') && value.ends_with('\'') {
+        value[1..value.len() - 1].replace("''", "'")
+    } else if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        value[1..value.len() - 1].replace("\\\"", "\"")
+    } else {
+        value.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const CONTENT: &str = "# Header\nmodule:\n  name: Test\n\n# The goal\nG1:\n  text: |\n    # Not a comment\n    Goal\n  # Before list\n  supportedBy:\n    - S1 # first\n    # between\n    - S2\n  inContextOf: [C1, 'C2'] # context\n\nS1:\n  text: Strategy\n  undeveloped: true\n# Trailer\n";
+
+    #[test]
+    fn round_trip() {
+        let mut source = SourceFile::parse(CONTENT);
+        assert_eq!(source.to_string(), CONTENT);
+        assert_eq!(
+            source
+                .entries
+                .iter()
+                .map(|e| e.key.as_str())
+                .collect::<Vec<_>>(),
+            ["module", "G1", "S1"]
+        );
+        let g1 = source.entry_mut("G1").unwrap();
+        assert_eq!(g1.leading, ["", "# The goal"]);
+        assert_eq!(
+            g1.attributes
+                .iter()
+                .map(|a| a.key.as_str())
+                .collect::<Vec<_>>(),
+            ["text", "supportedBy", "inContextOf"]
+        );
+        assert_eq!(g1.attribute_mut("text").unwrap().lines.len(), 3);
+        assert_eq!(
+            g1.attribute_mut("supportedBy").unwrap().leading,
+            ["  # Before list"]
+        );
+        assert_eq!(source.trailer, ["# Trailer"]);
+    }
+
+    #[test]
+    fn round_trip_without_final_newline() {
+        let content = "G1:\r\n  text: Goal";
+        assert_eq!(SourceFile::parse(content).to_string(), content);
+    }
+
+    #[test]
+    fn references() {
+        let mut source = SourceFile::parse(CONTENT);
+        let g1 = source.entry_mut("G1").unwrap();
+        assert_eq!(
+            g1.attribute_mut("supportedBy").unwrap().references(),
+            (ListStyle::Block, vec!["S1".to_owned(), "S2".to_owned()])
+        );
+        assert_eq!(
+            g1.attribute_mut("inContextOf").unwrap().references(),
+            (ListStyle::Flow, vec!["C1".to_owned(), "C2".to_owned()])
+        );
+        let mut source =
+            SourceFile::parse("G1:\n  supportedBy: S1, S2\n  inContextOf: [C1,\n    C2]\n");
+        let g1 = source.entry_mut("G1").unwrap();
+        assert_eq!(
+            g1.attribute_mut("supportedBy").unwrap().references(),
+            (ListStyle::Scalar, vec!["S1, S2".to_owned()])
+        );
+        assert_eq!(
+            g1.attribute_mut("inContextOf").unwrap().references().1,
+            ["C1", "C2"]
+        );
+    }
+
+    #[test]
+    fn set_references() {
+        let mut source = SourceFile::parse(CONTENT);
+        let g1 = source.entry_mut("G1").unwrap();
+        g1.attribute_mut("inContextOf")
+            .unwrap()
+            .set_references(&["C2".to_owned(), "C 3".to_owned()]);
+        g1.attribute_mut("supportedBy")
+            .unwrap()
+            .set_references(&["S2".to_owned(), "S3".to_owned()]);
+        assert_eq!(
+            source.to_string(),
+            CONTENT
+                .replace(
+                    "  inContextOf: [C1, 'C2'] # context",
+                    "  inContextOf: [C2, C 3] # context"
+                )
+                .replace(
+                    "    - S1 # first\n    # between\n    - S2\n",
+                    "    # between\n    - S2\n    - S3 # first\n"
+                )
+        );
+    }
+
+    #[test]
+    fn set_attributes() {
+        let mut source = SourceFile::parse(CONTENT);
+        let s1 = source.entry_mut("S1").unwrap();
+        assert!(s1.set_attribute("undeveloped", "false"));
+        assert!(s1.set_attribute("nodeType", "Strategy"));
+        assert_eq!(
+            source.to_string(),
+            CONTENT.replace(
+                "  text: Strategy\n  undeveloped: true\n",
+                "  text: Strategy\n  undeveloped: false\n  nodeType: Strategy\n"
+            )
+        );
+        let mut source = SourceFile::parse("G1: {text: Goal}\n");
+        assert!(
+            !source
+                .entry_mut("G1")
+                .unwrap()
+                .set_attribute("undeveloped", "true")
+        );
+    }
+
+    #[test]
+    fn rename_and_replace() {
+        let mut source = SourceFile::parse(
+            "G1:\n  supportedBy: [S1, S10] # S1\n  challenges: S2 -> G2\n  multiplicity:\n    S1: n\n    'S1': 'S1 x'\n",
+        );
+        let g1 = source.entry_mut("G1").unwrap();
+        g1.rename("G0");
+        assert_eq!(
+            g1.attributes
+                .iter_mut()
+                .map(|a| a.replace_scalars(&BTreeMap::from([("S1", "S2"), ("S2", "S1")])))
+                .sum::<usize>(),
+            4
+        );
+        assert_eq!(
+            source.to_string(),
+            "G0:\n  supportedBy: [S2, S10] # S1\n  challenges: S1 -> G2\n  multiplicity:\n    S2: n\n    S2: 'S1 x'\n"
+        );
+    }
+
+    #[test]
+    fn yaml_strings() {
+        assert_eq!(yaml_string("G1"), "G1");
+        assert_eq!(yaml_string("true"), "'true'");
+        assert_eq!(yaml_string("1.5"), "'1.5'");
+        assert_eq!(yaml_string("A: B"), "'A: B'");
+        assert_eq!(yaml_string("it's"), "'it''s'");
+        assert_eq!(unquote("'it''s'"), "it's");
+    }
+}
@@ -0,0 +1,218 @@
+use super::edit::{ListStyle, SourceFile};
+use crate::diagnostics::Diagnostics;
+use crate::file_utils::is_json_file;
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+
+///
+/// Checks whose findings can be fixed mechanically.
+///
+pub const FIXABLE_CHECKS: [&str; 3] = ["C11", "V02", "V05"];
+
+///
+/// An edit applied to an input file.
+///
+#[derive(Debug, PartialEq)]
+pub struct Fix {
+    pub file: String,
+    pub element: String,
+    pub description: String,
+}
+
+///
+/// A finding that can be fixed.
+///
+struct Finding<'a> {
+    check: &'a str,
+    element: &'a str,
+    reference: Option<&'a str>,
+}
+
+///
+/// Fix all findings in `diags` that can be fixed mechanically.
+///
+/// The input files are modified in place and the applied edits are returned.
+/// Suppressed and baselined findings are not part of `diags` and are therefore not fixed.
+/// JSON files are not modified.
+///
+pub fn fix_findings(diags: &Diagnostics) -> Result<Vec<Fix>> {
+    let mut findings: BTreeMap<&str, Vec<Finding>> = BTreeMap::new();
+    for msg in &diags.messages {
+        if let (Some(check), Some(location), Some(element)) =
+            (msg.get_check_id(), &msg.location, msg.elements.first())
+            && FIXABLE_CHECKS.contains(&check)
+            && !is_json_file(&location.file)
+        {
+            findings
+                .entry(location.file.as_str())
+                .or_default()
+                .push(Finding {
+                    check,
+                    element,
+                    reference: msg.elements.get(1).map(String::as_str),
+                });
+        }
+    }
+    let mut fixes = vec![];
+    for (file, findings) in findings {
+        let content = std::fs::read_to_string(file)
+            .with_context(|| format!("Failed to open file {file}."))?;
+        let mut source = SourceFile::parse(&content);
+        let mut changed = false;
+        for finding in findings {
+            if let Some(description) = fix_finding(&mut source, &finding) {
+                changed = true;
+                fixes.push(Fix {
+                    file: file.to_owned(),
+                    element: finding.element.to_owned(),
+                    description,
+                });
+            }
+        }
+        if changed {
+            std::fs::write(file, source.to_string())
+                .with_context(|| format!("Failed to write file {file}."))?;
+        }
+    }
+    Ok(fixes)
+}
+
+///
+/// Apply the fix for `finding` to `source`.
+/// Returns a description of the edit, or None if nothing could be changed.
+///
+fn fix_finding(source: &mut SourceFile, finding: &Finding) -> Option<String> {
+    let entry = source
+        .entry_mut(finding.element)
+        .filter(|e| e.is_block_mapping())?;
+    match (finding.check, finding.reference) {
+        // A list was written as a comma-separated string
+        ("C11", Some(reference)) => ["supportedBy", "inContextOf"].iter().find_map(|&key| {
+            let attribute = entry.attribute_mut(key)?;
+            let (_, references) = attribute.references();
+            references.iter().any(|r| r == reference).then(|| {
+                let references = references
+                    .iter()
+                    .flat_map(|r| r.split(','))
+                    .map(|r| r.trim().to_owned())
+                    .filter(|r| !r.is_empty())
+                    .collect::<Vec<_>>();
+                attribute.set_references(&references);
+                format!("Changed {key} to the list [{}].", references.join(", "))
+            })
+        }),
+        // Duplicate reference
+        ("V05", Some(reference)) => {
+            let mut keys = vec![];
+            for attribute in entry.attributes.iter_mut() {
+                let (style, references) = attribute.references();
+                if style != ListStyle::Scalar
+                    && references.iter().filter(|&r| r == reference).count() > 1
+                {
+                    let mut seen = false;
+                    let references = references
+                        .into_iter()
+                        .filter(|r| r != reference || !std::mem::replace(&mut seen, true))
+                        .collect::<Vec<_>>();
+                    attribute.set_references(&references);
+                    keys.push(attribute.key.to_owned());
+                }
+            }
+            (!keys.is_empty()).then(|| {
+                format!(
+                    "Removed duplicate entry {reference} from {}.",
+                    keys.join(", ")
+                )
+            })
+        }
+        // Leaf goal or strategy
+        ("V02", _) => entry
+            .set_attribute("undeveloped", "true")
+            .then(|| "Added undeveloped: true.".to_owned()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn fix(content: &str, check: &str, element: &str, reference: Option<&str>) -> String {
+        let mut source = SourceFile::parse(content);
+        assert!(
+            fix_finding(
+                &mut source,
+                &Finding {
+                    check,
+                    element,
+                    reference
+                }
+            )
+            .is_some()
+        );
+        source.to_string()
+    }
+
+    #[test]
+    fn comma_separated_list() {
+        assert_eq!(
+            fix(
+                "G1:\n  text: Goal # goal\n  supportedBy: S1, S2 # both\n",
+                "C11",
+                "G1",
+                Some("S1, S2")
+            ),
+            "G1:\n  text: Goal # goal\n  supportedBy: [S1, S2] # both\n"
+        );
+    }
+
+    #[test]
+    fn duplicate_entry() {
+        assert_eq!(
+            fix(
+                "G1:\n  inContextOf: [C1, C2, C1]\n  supportedBy:\n    - S1\n    - S1 # again\n",
+                "V05",
+                "G1",
+                Some("C1")
+            ),
+            "G1:\n  inContextOf: [C1, C2]\n  supportedBy:\n    - S1\n    - S1 # again\n"
+        );
+        assert_eq!(
+            fix(
+                "G1:\n  supportedBy:\n    - S1\n    - S1 # again\n    - S2\n",
+                "V05",
+                "G1",
+                Some("S1")
+            ),
+            "G1:\n  supportedBy:\n    - S1\n    # again\n    - S2\n"
+        );
+    }
+
+    #[test]
+    fn undeveloped() {
+        assert_eq!(
+            fix("# Goal\nG1:\n  text: Goal\n\nG2:\n", "V02", "G1", None),
+            "# Goal\nG1:\n  text: Goal\n  undeveloped: true\n\nG2:\n"
+        );
+        assert_eq!(
+            fix("G1:\n  undeveloped: false # todo\n", "V02", "G1", None),
+            "G1:\n  undeveloped: true # todo\n"
+        );
+    }
+
+    #[test]
+    fn not_fixable() {
+        let mut source = SourceFile::parse("G1: {text: Goal}\n");
+        assert!(
+            fix_finding(
+                &mut source,
+                &Finding {
+                    check: "V02",
+                    element: "G1",
+                    reference: None
+                }
+            )
+            .is_none()
+        );
+    }
+}
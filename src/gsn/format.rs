@@ -0,0 +1,217 @@
+use super::MODULE_ATTRIBUTES;
+use super::edit::SourceFile;
+
+///
+/// Canonical order of the attributes of an element.
+///
+pub const ELEMENT_ORDER: [&str; 20] = [
+    "text",
+    "nodeType",
+    "inContextOf",
+    "supportedBy",
+    "challenges",
+    "defeatedRelation",
+    "defeated",
+    "undeveloped",
+    "uninstantiated",
+    "public",
+    "optional",
+    "choice",
+    "multiplicity",
+    "acp",
+    "classes",
+    "url",
+    "rankIncrement",
+    "horizontalIndex",
+    "charWrap",
+    "suppress",
+];
+
+///
+/// Attributes of elements that are written as flow sequences.
+///
+const ELEMENT_LISTS: [&str; 4] = ["inContextOf", "supportedBy", "defeatedRelation", "optional"];
+
+///
+/// Attributes of the module information that are written as flow sequences.
+///
+const MODULE_LISTS: [&str; 3] = ["uses", "interface", "roots"];
+
+///
+/// Indentation of attributes
+///
+const INDENT: usize = 2;
+
+///
+/// Format `content` in the canonical layout.
+///
+/// - Attributes are ordered according to [`ELEMENT_ORDER`] or [`MODULE_ATTRIBUTES`].
+///   Unknown attributes follow in their original order.
+/// - Attributes are indented by two spaces.
+/// - Lists of references are written as flow sequences.
+/// - Entries are separated by exactly one blank line. Blank lines between attributes are removed.
+/// - Trailing whitespace is removed from the lines of keys.
+///
+/// Comments are kept. Entries that are not block mappings are not modified.
+///
+pub fn format_source(content: &str) -> String {
+    let mut source = SourceFile::parse(content);
+    for (index, entry) in source.entries.iter_mut().enumerate() {
+        normalize_blank_lines(&mut entry.leading);
+        entry.line.truncate(entry.line.trim_end().len());
+        if index > 0 {
+            entry.leading.insert(0, String::new());
+        }
+        if !entry.is_block_mapping() {
+            continue;
+        }
+        let (order, lists) = if entry.key == "module" {
+            (&MODULE_ATTRIBUTES[..], &MODULE_LISTS[..])
+        } else {
+            (&ELEMENT_ORDER[..], &ELEMENT_LISTS[..])
+        };
+        for attribute in entry.attributes.iter_mut() {
+            attribute.leading.retain(|l| !l.trim().is_empty());
+            attribute.reindent(INDENT);
+            if let Some(line) = attribute.lines.first_mut() {
+                line.truncate(line.trim_end().len());
+            }
+            if lists.contains(&attribute.key.as_str()) {
+                let (_, references) = attribute.references();
+                attribute.set_flow_references(&references);
+            }
+        }
+        // Stable sort keeps the order of unknown attributes
+        entry.attributes.sort_by_key(|a| {
+            order
+                .iter()
+                .position(|&k| k == a.key)
+                .unwrap_or(order.len())
+        });
+    }
+    normalize_blank_lines(&mut source.trailer);
+    if source.trailer.last().is_some_and(|l| l.trim().is_empty()) {
+        source.trailer.pop();
+    }
+    if !source.trailer.is_empty() && !source.entries.is_empty() {
+        source.trailer.insert(0, String::new());
+    }
+    source.final_newline = true;
+    source.to_string()
+}
+
+///
+/// Remove leading blank lines and collapse consecutive blank lines.
+///
+fn normalize_blank_lines(lines: &mut Vec<String>) {
+    let mut previous_blank = true;
+    lines.retain(|l| {
+        let blank = l.trim().is_empty();
+        let keep = !(blank && previous_blank);
+        previous_blank = blank;
+        keep
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::gsn::ELEMENT_ATTRIBUTES;
+    use serde_json::Value;
+    use serde_saphyr::RequireIndent;
+    use std::path::{Path, PathBuf};
+
+    fn collect_yaml_files(dir: &Path, files: &mut Vec<PathBuf>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                collect_yaml_files(&path, files);
+            } else if path.extension().is_some_and(|e| e == "yaml")
+                // Bindings for pattern instantiation have a different format.
+                && !path.to_string_lossy().ends_with(".binding.yaml")
+            {
+                files.push(path);
+            }
+        }
+    }
+
+    ///
+    /// A single reference is equivalent to a list with one reference.
+    ///
+    fn single_references_as_lists(document: &mut Value) {
+        for entry in document
+            .as_object_mut()
+            .into_iter()
+            .flat_map(|d| d.values_mut())
+        {
+            for (key, value) in entry.as_object_mut().into_iter().flatten() {
+                if (ELEMENT_LISTS.contains(&key.as_str()) || MODULE_LISTS.contains(&key.as_str()))
+                    && value.is_string()
+                {
+                    *value = Value::Array(vec![value.take()]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn element_order() {
+        let mut order = ELEMENT_ORDER.to_vec();
+        let mut attributes = ELEMENT_ATTRIBUTES.to_vec();
+        order.sort();
+        attributes.sort();
+        assert_eq!(order, attributes);
+    }
+
+    #[test]
+    fn canonical_layout() {
+        let content = "# Header\n\n\nmodule:\n    brief: Brief\n    name: Test\n    uses:\n    - sub.gsn.yaml\nG1:\n    supportedBy:\n      - S1 # first\n      - S2\n\n    # The text\n    text: |\n      Goal\n        indented\n    inContextOf: C1\n    custom: value\n    nodeType: Goal\n\n\n\nS1: {text: Strategy}\n# End\n\n";
+        assert_eq!(
+            format_source(content),
+            "# Header\n\nmodule:\n  name: Test\n  brief: Brief\n  uses: [sub.gsn.yaml]\n\nG1:\n  # The text\n  text: |\n    Goal\n      indented\n  nodeType: Goal\n  inContextOf: [C1]\n  supportedBy: [S1, S2] # first\n  custom: value\n\nS1: {text: Strategy}\n\n# End\n"
+        );
+    }
+
+    #[test]
+    fn nested_indentation() {
+        let content = "Sn1:\n text: >-\n   Solution\n    indented\n horizontalIndex:\n  relative: -1\n acp:\n  ACP1: [G1]\n";
+        assert_eq!(
+            format_source(content),
+            "Sn1:\n  text: >-\n    Solution\n     indented\n  acp:\n    ACP1: [G1]\n  horizontalIndex:\n    relative: -1\n"
+        );
+    }
+
+    #[test]
+    fn formatting_is_stable() {
+        let content = "G1:\n  text: Goal\n  supportedBy: [S1]\n\nS1:\n  text: Strategy\n  undeveloped: true\n";
+        assert_eq!(format_source(content), content);
+        assert_eq!(format_source(""), "");
+    }
+
+    ///
+    /// Formatting must not change the content of any example.
+    ///
+    #[test]
+    fn examples_keep_content() {
+        let mut files = vec![];
+        collect_yaml_files(Path::new("examples"), &mut files);
+        collect_yaml_files(Path::new("tests"), &mut files);
+        assert!(!files.is_empty());
+        for file in files {
+            let content = std::fs::read_to_string(&file).unwrap();
+            let Ok(mut original) = serde_saphyr::from_str::<Value>(&content) else {
+                continue;
+            };
+            let formatted = format_source(&content);
+            let options = serde_saphyr::options! {
+                require_indent: RequireIndent::Uniform(None),
+            };
+            let mut reformatted: Value = serde_saphyr::from_str_with_options(&formatted, options)
+                .unwrap_or_else(|e| panic!("{}: {e}\n{formatted}", file.display()));
+            single_references_as_lists(&mut original);
+            single_references_as_lists(&mut reformatted);
+            assert_eq!(original, reformatted, "{}", file.display());
+            assert_eq!(format_source(&formatted), formatted, "{}", file.display());
+        }
+    }
+}
@@ -0,0 +1,689 @@
+use super::{
+    Challenge, ExtendsModule, GsnNode, Module, ModuleInformation, Multiplicity, Origin,
+    get_node_type_from_text,
+};
+use crate::{
+    diagnostics::{Diagnostics, SourceLocation},
+    file_utils::get_relative_path,
+};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+///
+/// The binding of a pattern to concrete values.
+/// It is read from a YAML file.
+///
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Binding {
+    pub(crate) pattern: String,
+    pub(crate) module: ModuleInformation,
+    #[serde(default)]
+    pub(crate) id_suffix: String,
+    #[serde(default)]
+    pub(crate) parameters: BTreeMap<String, String>,
+    #[serde(default)]
+    pub(crate) replicate: BTreeMap<String, Replication>,
+    #[serde(default, deserialize_with = "super::deser_string_or_seq_string")]
+    pub(crate) omit: Vec<String>,
+    pub(crate) output: Option<String>,
+}
+
+///
+/// How often an element with multiplicity is instantiated.
+/// Either just a number, or a list of parameter values for each instance.
+///
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Replication {
+    Count(usize),
+    Values(Vec<BTreeMap<String, String>>),
+}
+
+///
+/// The elements and the module generated from a pattern
+///
+type Instance = (BTreeMap<String, GsnNode>, BTreeMap<String, Module>);
+
+///
+/// State while instantiating the pattern
+///
+struct Instantiation<'a> {
+    binding: &'a Binding,
+    nodes: &'a BTreeMap<String, GsnNode>,
+    pattern: BTreeMap<&'a str, &'a GsnNode>,
+    omitted: BTreeSet<&'a str>,
+    shared: BTreeSet<&'a str>,
+    result: BTreeMap<String, GsnNode>,
+    /// IDs of the instances of each pattern element
+    instances: BTreeMap<String, Vec<String>>,
+    /// Instantiated challengers with the suffix of their instance
+    challengers: Vec<(String, String)>,
+    location: SourceLocation,
+    errors: usize,
+}
+
+///
+/// Instantiate the pattern module named in `binding`.
+///
+/// Parameters in the form of `{Parameter}` in the text of elements are replaced by their bound values.
+/// Elements that are related with a multiplicity are replicated as often as the binding says.
+/// Their IDs get a suffix `_1`, `_2`, ... for each instance.
+/// Elements referenced by more than one element of the pattern are instantiated only once.
+/// Omitted optional elements and alternatives of a choice are removed with all elements only they reference.
+///
+/// The generated module extends the pattern module.
+/// `binding_path` is used to calculate the path to the pattern, if the generated module is written to a file.
+///
+pub fn instantiate(
+    diags: &mut Diagnostics,
+    binding: &Binding,
+    binding_path: &str,
+    nodes: &BTreeMap<String, GsnNode>,
+    modules: &BTreeMap<String, Module>,
+) -> Result<Instance, ()> {
+    let module_name = binding.module.name.to_owned();
+    let location = SourceLocation {
+        file: binding_path.to_owned(),
+        line: 1,
+        column: 1,
+    };
+    let Some(pattern_module) = modules.get(&binding.pattern) else {
+        diags.add_error_at(
+            Some(&module_name),
+            Some(&location),
+            &[],
+            format!(
+                "C17: Pattern module {} is not found, but is supposed to be instantiated.",
+                binding.pattern
+            ),
+        );
+        return Err(());
+    };
+    let mut inst = Instantiation {
+        binding,
+        nodes,
+        pattern: nodes
+            .iter()
+            .filter(|(_, n)| n.module == binding.pattern)
+            .map(|(id, n)| (id.as_str(), n))
+            .collect(),
+        omitted: BTreeSet::new(),
+        shared: BTreeSet::new(),
+        result: BTreeMap::new(),
+        instances: BTreeMap::new(),
+        challengers: vec![],
+        location,
+        errors: 0,
+    };
+    inst.check_binding(diags);
+    if inst.errors > 0 {
+        return Err(());
+    }
+    // Pattern roots are elements not referenced from within the pattern
+    let mut referenced: BTreeSet<&str> = BTreeSet::new();
+    for target in inst
+        .pattern
+        .values()
+        .flat_map(|n| n.supported_by.iter().chain(n.in_context_of.iter()))
+    {
+        if !referenced.insert(target) {
+            inst.shared.insert(target);
+        }
+    }
+    let roots: Vec<&str> = inst
+        .pattern
+        .keys()
+        .filter(|id| !referenced.contains(*id) && !inst.omitted.contains(*id))
+        .copied()
+        .collect();
+    for root in roots {
+        inst.instantiate_node(diags, root, &binding.parameters, "");
+    }
+    inst.map_challenges(diags);
+    if inst.errors > 0 {
+        return Err(());
+    }
+
+    let mut meta = binding.module.clone();
+    meta.extends.push(ExtendsModule {
+        module: binding.pattern.to_owned(),
+        develops: BTreeMap::new(),
+    });
+    let output_path = binding
+        .output
+        .as_ref()
+        .map(|o| resolve_relative_to(binding_path, o))
+        .unwrap_or_else(|| binding_path.to_owned());
+    meta.uses.push(get_relative_path(
+        &pattern_module.orig_file_name,
+        &output_path,
+    ));
+    let module = Module {
+        orig_file_name: output_path,
+        document: None,
+        canonical_path: None,
+        output_path: None,
+        origin: Origin::File(binding_path.to_owned()),
+        meta,
+        location: Some(inst.location),
+        sibling_order: pattern_module.sibling_order,
+    };
+    Ok((inst.result, BTreeMap::from([(module_name, module)])))
+}
+
+///
+/// Resolve `path` relative to the directory of `file`.
+///
+pub fn resolve_relative_to(file: &str, path: &str) -> String {
+    match std::path::Path::new(file).parent() {
+        Some(dir) if std::path::Path::new(path).is_relative() => dir
+            .join(path)
+            .to_string_lossy()
+            .to_string()
+            .replace('\\', "/"),
+        _ => path.to_owned(),
+    }
+}
+
+impl<'a> Instantiation<'a> {
+    ///
+    /// Check if the binding fits to the pattern.
+    ///
+    /// - Only optional elements and alternatives of a choice can be omitted.
+    /// - At least one alternative of a choice must remain.
+    /// - Only elements with multiplicity can be replicated.
+    ///
+    fn check_binding(&mut self, diags: &mut Diagnostics) {
+        let module = self.binding.module.name.as_str();
+        for omit in &self.binding.omit {
+            if self.pattern.values().any(|n| {
+                n.optional.contains(omit) || n.choice.values().flatten().any(|c| c == omit)
+            }) {
+                self.omitted.insert(omit);
+            } else {
+                diags.add_error_at(
+                    Some(module),
+                    Some(&self.location),
+                    &[omit.as_str()],
+                    format!(
+                        "C17: Element {omit} is neither optional nor an alternative of a choice in pattern {}, but is supposed to be omitted.",
+                        self.binding.pattern
+                    ),
+                );
+                self.errors += 1;
+            }
+        }
+        for (id, node) in &self.pattern {
+            for (choice, alternatives) in &node.choice {
+                if alternatives
+                    .iter()
+                    .all(|a| self.omitted.contains(a.as_str()))
+                {
+                    diags.add_error_at(
+                        Some(module),
+                        Some(&self.location),
+                        &[*id],
+                        format!(
+                            "C17: All alternatives of choice {choice} of element {id} are omitted."
+                        ),
+                    );
+                    self.errors += 1;
+                }
+            }
+        }
+        for target in self.binding.replicate.keys() {
+            if !self
+                .pattern
+                .values()
+                .any(|n| n.multiplicity.contains_key(target))
+            {
+                diags.add_error_at(
+                    Some(module),
+                    Some(&self.location),
+                    &[target.as_str()],
+                    format!(
+                        "C17: Element {target} has no multiplicity in pattern {}, but is supposed to be replicated.",
+                        self.binding.pattern
+                    ),
+                );
+                self.errors += 1;
+            }
+        }
+    }
+
+    ///
+    /// Instantiate the pattern element `id` and everything it references.
+    /// Returns the IDs of the instances.
+    ///
+    fn instantiate_node(
+        &mut self,
+        diags: &mut Diagnostics,
+        id: &str,
+        parameters: &BTreeMap<String, String>,
+        suffix: &str,
+    ) -> String {
+        let new_id = format!("{id}{suffix}{}", self.binding.id_suffix);
+        if self.result.contains_key(&new_id) {
+            return new_id;
+        }
+        // unwrap ok, since only references within the pattern are followed
+        let pattern_node = *self.pattern.get(id).unwrap();
+        let module = self.binding.module.name.to_owned();
+        if self.nodes.contains_key(&new_id) {
+            diags.add_error_at(
+                Some(&module),
+                Some(&self.location),
+                &[new_id.as_str()],
+                format!(
+                    "C17: Element {new_id} already exists. Please choose a different idSuffix."
+                ),
+            );
+            self.errors += 1;
+        }
+        let mut node = pattern_node.clone();
+        node.text = self.expand_parameters(diags, &new_id, pattern_node, parameters);
+        node.module = module.to_owned();
+        node.uninstantiated = false;
+        node.multiplicity.clear();
+        node.optional.clear();
+        node.choice.clear();
+        if node.node_type == get_node_type_from_text(&new_id) {
+            node.node_type = None;
+        }
+        // Reserve ID, since elements may be referenced multiple times
+        self.result.insert(new_id.to_owned(), GsnNode::default());
+        self.instances
+            .entry(id.to_owned())
+            .or_default()
+            .push(new_id.to_owned());
+        if pattern_node.challenges.is_some() {
+            self.challengers
+                .push((new_id.to_owned(), suffix.to_owned()));
+        }
+
+        let mut instances: BTreeMap<&str, Vec<String>> =
+            BTreeMap::from([(id, vec![new_id.to_owned()])]);
+        let targets = pattern_node
+            .supported_by
+            .iter()
+            .chain(pattern_node.in_context_of.iter())
+            .filter(|t| !self.omitted.contains(t.as_str()) && self.pattern.contains_key(t.as_str()))
+            .collect::<Vec<_>>();
+        for target in targets {
+            let target_instances = match pattern_node.get_edge_pattern(target).multiplicity {
+                Some(Multiplicity::Multiple(_)) => match self.binding.replicate.get(target) {
+                    Some(Replication::Count(count)) => (1..=*count)
+                        .map(|i| {
+                            self.instantiate_node(
+                                diags,
+                                target,
+                                parameters,
+                                &format!("{suffix}_{i}"),
+                            )
+                        })
+                        .collect(),
+                    Some(Replication::Values(values)) => values
+                        .iter()
+                        .enumerate()
+                        .map(|(i, values)| {
+                            let mut parameters = parameters.to_owned();
+                            parameters.extend(values.to_owned());
+                            self.instantiate_node(
+                                diags,
+                                target,
+                                &parameters,
+                                &format!("{suffix}_{}", i + 1),
+                            )
+                        })
+                        .collect(),
+                    None => {
+                        diags.add_error_at(
+                            Some(&module),
+                            Some(&self.location),
+                            &[target.as_str()],
+                            format!("C17: Element {target} has a multiplicity in pattern {}, but no replication is bound.", self.binding.pattern),
+                        );
+                        self.errors += 1;
+                        vec![]
+                    }
+                },
+                _ if self.shared.contains(target.as_str()) => {
+                    vec![self.instantiate_node(diags, target, &self.binding.parameters, "")]
+                }
+                _ => vec![self.instantiate_node(diags, target, parameters, suffix)],
+            };
+            instances.insert(target, target_instances);
+        }
+        let map_refs = |refs: &[String]| -> Vec<String> {
+            refs.iter()
+                .flat_map(|r| match instances.get(r.as_str()) {
+                    Some(i) => i.to_owned(),
+                    None if self.pattern.contains_key(r.as_str()) => vec![],
+                    None => vec![r.to_owned()],
+                })
+                .collect()
+        };
+        node.supported_by = map_refs(&pattern_node.supported_by);
+        node.in_context_of = map_refs(&pattern_node.in_context_of);
+        node.defeated_relation = map_refs(&pattern_node.defeated_relation);
+        node.acp = pattern_node
+            .acp
+            .iter()
+            .map(|(acp, refs)| (acp.to_owned(), map_refs(refs)))
+            .collect();
+        self.result.insert(new_id.to_owned(), node);
+        new_id
+    }
+
+    ///
+    /// Map the elements challenged by instantiated challengers to their instances.
+    /// This is done after all elements are instantiated, since challengers are usually roots of the pattern.
+    ///
+    /// An element with several instances is mapped to the instance that is replicated together with the challenger.
+    /// If there is no such instance, it is reported as error.
+    ///
+    fn map_challenges(&mut self, diags: &mut Diagnostics) {
+        for (new_id, suffix) in std::mem::take(&mut self.challengers) {
+            // unwrap ok, since challengers are instantiated
+            let node = self.result.get_mut(&new_id).unwrap();
+            let refs = match node.challenges.as_mut() {
+                Some(Challenge::Node(n)) => vec![n],
+                Some(Challenge::Relation((l, r))) => vec![l, r],
+                None => vec![],
+            };
+            for r in refs {
+                let Some(instances) = self.instances.get(r.as_str()) else {
+                    continue;
+                };
+                let same_replication = format!("{r}{suffix}{}", self.binding.id_suffix);
+                if instances.contains(&same_replication) {
+                    *r = same_replication;
+                } else if let [instance] = instances.as_slice() {
+                    *r = instance.to_owned();
+                } else {
+                    diags.add_error_at(
+                        Some(&self.binding.module.name),
+                        Some(&self.location),
+                        &[new_id.as_str(), r.as_str()],
+                        format!(
+                            "C17: Element {new_id} challenges element {r} that has several instances, but is not replicated together with it."
+                        ),
+                    );
+                    self.errors += 1;
+                }
+            }
+        }
+    }
+
+    ///
+    /// Replace `{Parameter}` in `text` by the bound value.
+    /// Unbound parameters are kept and reported as warning.
+    ///
+    fn expand_parameters(
+        &self,
+        diags: &mut Diagnostics,
+        id: &str,
+        pattern_node: &GsnNode,
+        parameters: &BTreeMap<String, String>,
+    ) -> String {
+        let mut result = String::new();
+        let mut rest = pattern_node.text.as_str();
+        while let Some(start) = rest.find('{') {
+            result.push_str(&rest[..start]);
+            match rest[start + 1..].find(['}', '{', '\n']) {
+                Some(len) if rest[start + 1 + len..].starts_with('}') => {
+                    let parameter = &rest[start + 1..start + 1 + len];
+                    if let Some(value) = parameters.get(parameter) {
+                        result.push_str(value);
+                    } else {
+                        diags.add_warning_at(
+                            Some(&self.binding.module.name),
+                            pattern_node.get_location(),
+                            &[id],
+                            format!("C18: Parameter {parameter} of element {id} is not bound."),
+                        );
+                        result.push_str(&rest[start..start + len + 2]);
+                    }
+                    rest = &rest[start + len + 2..];
+                }
+                _ => {
+                    result.push('{');
+                    rest = &rest[start + 1..];
+                }
+            }
+        }
+        result.push_str(rest);
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::diagnostics::DiagType;
+
+    fn pattern() -> BTreeMap<String, GsnNode> {
+        parse_pattern(
+            r#"
+G1:
+  text: "{System} is safe"
+  supportedBy: [S1]
+  inContextOf: [C1]
+C1:
+  text: "{System} description"
+S1:
+  text: Argument over hazards
+  supportedBy: [G2, G3]
+  multiplicity:
+    G2: "n"
+  optional: G3
+G2:
+  text: "{Hazard} is mitigated"
+  supportedBy: [Sn1]
+  inContextOf: [C1]
+G3:
+  text: Residual risk is acceptable
+  undeveloped: true
+  uninstantiated: true
+Sn1:
+  text: Evidence for {Hazard}
+"#,
+        )
+    }
+
+    fn parse_pattern(gsn: &str) -> BTreeMap<String, GsnNode> {
+        let mut nodes: BTreeMap<String, GsnNode> = serde_saphyr::from_str(gsn).unwrap();
+        for (id, n) in nodes.iter_mut() {
+            n.module = "P".to_owned();
+            n.fix_node_type(id);
+        }
+        nodes
+    }
+
+    fn modules() -> BTreeMap<String, Module> {
+        BTreeMap::from([(
+            "P".to_owned(),
+            Module {
+                orig_file_name: "pattern.gsn.yaml".to_owned(),
+                meta: ModuleInformation::new("P".to_owned()),
+                ..Default::default()
+            },
+        )])
+    }
+
+    #[test]
+    fn replicate_and_omit() {
+        let mut d = Diagnostics::default();
+        let binding: Binding = serde_saphyr::from_str(
+            r#"
+pattern: P
+module:
+  name: I
+idSuffix: _B
+parameters:
+  System: Brake
+replicate:
+  G2:
+    - Hazard: Loss of braking
+    - Hazard: Unintended braking
+omit: G3
+output: instance.gsn.yaml
+"#,
+        )
+        .unwrap();
+        let (nodes, modules) =
+            instantiate(&mut d, &binding, "binding.yaml", &pattern(), &modules()).unwrap();
+        assert_eq!(d.messages.len(), 0);
+        assert_eq!(
+            nodes.keys().collect::<Vec<_>>(),
+            [
+                "C1_B", "G1_B", "G2_1_B", "G2_2_B", "S1_B", "Sn1_1_B", "Sn1_2_B"
+            ]
+        );
+        assert_eq!(nodes["G1_B"].text, "Brake is safe");
+        assert_eq!(nodes["S1_B"].supported_by, ["G2_1_B", "G2_2_B"]);
+        assert_eq!(nodes["G2_2_B"].text, "Unintended braking is mitigated");
+        assert_eq!(nodes["G2_2_B"].in_context_of, ["C1_B"]);
+        assert_eq!(nodes["Sn1_1_B"].text, "Evidence for Loss of braking");
+        assert!(nodes["S1_B"].multiplicity.is_empty());
+        assert!(nodes.values().all(|n| n.module == "I"));
+        let meta = &modules["I"].meta;
+        assert_eq!(meta.extends[0].module, "P");
+        assert_eq!(meta.uses, ["pattern.gsn.yaml"]);
+    }
+
+    #[test]
+    fn invalid_binding() {
+        let mut d = Diagnostics::default();
+        let binding = Binding {
+            pattern: "P".to_owned(),
+            module: ModuleInformation::new("I".to_owned()),
+            replicate: BTreeMap::from([("G3".to_owned(), Replication::Count(2))]),
+            omit: vec!["Sn1".to_owned()],
+            ..Default::default()
+        };
+        assert!(instantiate(&mut d, &binding, "binding.yaml", &pattern(), &modules()).is_err());
+        assert_eq!(d.messages.len(), 2);
+        assert_eq!(d.messages[0].diag_type, DiagType::Error);
+        assert_eq!(
+            d.messages[0].msg,
+            "C17: Element Sn1 is neither optional nor an alternative of a choice in pattern P, but is supposed to be omitted."
+        );
+        assert_eq!(
+            d.messages[1].msg,
+            "C17: Element G3 has no multiplicity in pattern P, but is supposed to be replicated."
+        );
+    }
+
+    #[test]
+    fn unbound_parameter() {
+        let mut d = Diagnostics::default();
+        let binding = Binding {
+            pattern: "P".to_owned(),
+            module: ModuleInformation::new("I".to_owned()),
+            id_suffix: "_I".to_owned(),
+            replicate: BTreeMap::from([("G2".to_owned(), Replication::Count(1))]),
+            ..Default::default()
+        };
+        let (nodes, _) =
+            instantiate(&mut d, &binding, "binding.yaml", &pattern(), &modules()).unwrap();
+        assert_eq!(nodes["G2_1_I"].text, "{Hazard} is mitigated");
+        assert!(nodes["G3_I"].undeveloped);
+        assert!(!nodes["G3_I"].uninstantiated);
+        assert_eq!(d.warnings, 4);
+        assert_eq!(
+            d.messages[0].msg,
+            "C18: Parameter System of element G1_I is not bound."
+        );
+    }
+
+    fn challenge_pattern() -> BTreeMap<String, GsnNode> {
+        parse_pattern(
+            r#"
+G1:
+  supportedBy: [S1]
+S1:
+  supportedBy: [G2]
+  multiplicity:
+    G2: "n"
+G2:
+  supportedBy: [Sn1, CSn1]
+Sn1: {}
+CSn1:
+  challenges: Sn1
+CG1:
+  challenges: G1 -> S1
+"#,
+        )
+    }
+
+    #[test]
+    fn replicated_challenges() {
+        let mut d = Diagnostics::default();
+        let binding = Binding {
+            pattern: "P".to_owned(),
+            module: ModuleInformation::new("I".to_owned()),
+            id_suffix: "_I".to_owned(),
+            replicate: BTreeMap::from([("G2".to_owned(), Replication::Count(2))]),
+            ..Default::default()
+        };
+        let (nodes, _) = instantiate(
+            &mut d,
+            &binding,
+            "binding.yaml",
+            &challenge_pattern(),
+            &modules(),
+        )
+        .unwrap();
+        assert_eq!(d.messages.len(), 0);
+        assert_eq!(
+            nodes["CSn1_1_I"].challenges,
+            Some(Challenge::Node("Sn1_1_I".to_owned()))
+        );
+        assert_eq!(
+            nodes["CSn1_2_I"].challenges,
+            Some(Challenge::Node("Sn1_2_I".to_owned()))
+        );
+        assert_eq!(
+            nodes["CG1_I"].challenges,
+            Some(Challenge::Relation(("G1_I".to_owned(), "S1_I".to_owned())))
+        );
+    }
+
+    #[test]
+    fn challenge_of_replicated_element() {
+        let mut d = Diagnostics::default();
+        let mut nodes = challenge_pattern();
+        // unwrap ok, since CG1 is part of the pattern
+        nodes.get_mut("CG1").unwrap().challenges = Some(Challenge::Node("Sn1".to_owned()));
+        let binding = Binding {
+            pattern: "P".to_owned(),
+            module: ModuleInformation::new("I".to_owned()),
+            id_suffix: "_I".to_owned(),
+            replicate: BTreeMap::from([("G2".to_owned(), Replication::Count(2))]),
+            ..Default::default()
+        };
+        assert!(instantiate(&mut d, &binding, "binding.yaml", &nodes, &modules()).is_err());
+        assert_eq!(d.errors, 1);
+        assert_eq!(
+            d.messages[0].msg,
+            "C17: Element CG1_I challenges element Sn1 that has several instances, but is not replicated together with it."
+        );
+    }
+
+    #[test]
+    fn missing_pattern() {
+        let mut d = Diagnostics::default();
+        let binding = Binding {
+            pattern: "X".to_owned(),
+            module: ModuleInformation::new("I".to_owned()),
+            ..Default::default()
+        };
+        assert!(instantiate(&mut d, &binding, "binding.yaml", &pattern(), &modules()).is_err());
+        assert_eq!(
+            d.messages[0].msg,
+            "C17: Pattern module X is not found, but is supposed to be instantiated."
+        );
+    }
+}
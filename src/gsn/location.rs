@@ -0,0 +1,322 @@
+use crate::diagnostics::SourceLocation;
+use serde::{Deserialize, Deserializer, de};
+use serde_saphyr::{Location, Spanned};
+use std::{collections::BTreeMap, fmt, marker::PhantomData};
+
+///
+/// Source locations of an element and of its references
+///
+#[derive(Clone, Debug, Default)]
+pub struct ElementLocation {
+    pub location: SourceLocation,
+    pub references: BTreeMap<String, SourceLocation>,
+    /// Assurance claim points by their name
+    pub acp: BTreeMap<String, LabelLocation>,
+    /// Choices by their label
+    pub choice: BTreeMap<String, LabelLocation>,
+    /// Multiplicities by the related element
+    pub multiplicity: BTreeMap<String, SourceLocation>,
+}
+
+///
+/// Source locations of a labelled list of references, e.g. an assurance claim point or a choice
+///
+#[derive(Clone, Debug, Default)]
+pub struct LabelLocation {
+    pub location: SourceLocation,
+    pub references: BTreeMap<String, SourceLocation>,
+}
+
+///
+/// References to other elements as they appear in the input.
+/// A single reference has no location of its own; the location of the attribute is used instead.
+///
+enum SpannedReferences {
+    Single(String),
+    List(Vec<Spanned<String>>),
+}
+
+impl<'de> Deserialize<'de> for SpannedReferences {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct StringOrVec;
+
+        impl<'de> de::Visitor<'de> for StringOrVec {
+            type Value = SpannedReferences;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("string or list of strings")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(SpannedReferences::Single(value.to_owned()))
+            }
+
+            fn visit_seq<S>(self, mut visitor: S) -> Result<Self::Value, S::Error>
+            where
+                S: de::SeqAccess<'de>,
+            {
+                let mut references = vec![];
+                while let Some(reference) = visitor.next_element()? {
+                    references.push(reference);
+                }
+                Ok(SpannedReferences::List(references))
+            }
+        }
+
+        deserializer.deserialize_any(StringOrVec)
+    }
+}
+
+///
+/// The attributes of an element that reference other elements.
+/// All other attributes are ignored.
+///
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ElementReferences {
+    #[serde(default)]
+    in_context_of: Option<Spanned<SpannedReferences>>,
+    #[serde(default)]
+    supported_by: Option<Spanned<SpannedReferences>>,
+    #[serde(default)]
+    challenges: Option<Spanned<String>>,
+    #[serde(default)]
+    defeated_relation: Option<Spanned<SpannedReferences>>,
+    #[serde(default)]
+    optional: Option<Spanned<SpannedReferences>>,
+    #[serde(default)]
+    acp: Option<LocatedMap<Spanned<SpannedReferences>>>,
+    #[serde(default)]
+    choice: Option<LocatedMap<Spanned<SpannedReferences>>>,
+    #[serde(default)]
+    multiplicity: Option<LocatedMap<de::IgnoredAny>>,
+}
+
+///
+/// A map keeping the order and the locations of the keys.
+///
+struct LocatedMap<V>(Vec<(Spanned<String>, V)>);
+
+///
+/// A complete input document
+///
+type LocatedDocument = LocatedMap<Option<ElementReferences>>;
+
+impl<'de, V> Deserialize<'de> for LocatedMap<V>
+where
+    V: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct MapVisitor<V>(PhantomData<V>);
+
+        impl<'de, V> de::Visitor<'de> for MapVisitor<V>
+        where
+            V: Deserialize<'de>,
+        {
+            type Value = LocatedMap<V>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("map")
+            }
+
+            fn visit_map<M>(self, mut visitor: M) -> Result<Self::Value, M::Error>
+            where
+                M: de::MapAccess<'de>,
+            {
+                let mut entries = vec![];
+                while let Some(entry) = visitor.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(LocatedMap(entries))
+            }
+        }
+
+        deserializer.deserialize_map(MapVisitor(PhantomData))
+    }
+}
+
+///
+/// Read the source locations of all elements and their references from the YAML documents in `content`.
+///
+/// A map is returned for each document. The keys of the maps are the element IDs (incl. the module information).
+/// The documents must have been successfully parsed before, thus errors are not expected here.
+///
+pub fn read_locations(
+    file: &str,
+    content: &str,
+) -> Result<Vec<BTreeMap<String, ElementLocation>>, serde_saphyr::Error> {
+    let to_source_location = |location: &Location| SourceLocation {
+        file: file.to_owned(),
+        line: location.line(),
+        column: location.column(),
+    };
+    let documents: Vec<LocatedDocument> = serde_saphyr::from_multiple(content)?;
+    Ok(documents
+        .into_iter()
+        .map(|document| read_document_locations(document, &to_source_location))
+        .collect())
+}
+
+///
+/// Get the source locations of the elements and their references in `document`.
+///
+fn read_document_locations(
+    document: LocatedDocument,
+    to_source_location: &impl Fn(&Location) -> SourceLocation,
+) -> BTreeMap<String, ElementLocation> {
+    document
+        .0
+        .into_iter()
+        .map(|(id, references)| {
+            let mut element = ElementLocation {
+                location: to_source_location(&id.referenced),
+                ..Default::default()
+            };
+            if let Some(references) = references {
+                for attribute in [
+                    references.in_context_of,
+                    references.supported_by,
+                    references.defeated_relation,
+                    references.optional,
+                ]
+                .into_iter()
+                .flatten()
+                {
+                    add_references(&mut element.references, attribute, to_source_location);
+                }
+                let labelled = [
+                    (&mut element.acp, references.acp),
+                    (&mut element.choice, references.choice),
+                ];
+                for (locations, attribute) in labelled {
+                    for (label, targets) in attribute.into_iter().flat_map(|a| a.0) {
+                        let mut location = LabelLocation {
+                            location: to_source_location(&label.referenced),
+                            references: BTreeMap::new(),
+                        };
+                        add_references(&mut location.references, targets, to_source_location);
+                        locations.insert(label.value, location);
+                    }
+                }
+                for (target, _) in references.multiplicity.into_iter().flat_map(|m| m.0) {
+                    element
+                        .multiplicity
+                        .insert(target.value, to_source_location(&target.referenced));
+                }
+                if let Some(challenges) = references.challenges {
+                    // Either a single element or a relation in the form of X -> Y
+                    for target in challenges.value.split("->") {
+                        element
+                            .references
+                            .entry(target.trim().to_owned())
+                            .or_insert_with(|| to_source_location(&challenges.referenced));
+                    }
+                }
+            }
+            (id.value, element)
+        })
+        .collect()
+}
+
+///
+/// Add the locations of the references in `attribute` to `references`.
+/// Only the first location of a reference is kept.
+///
+fn add_references(
+    references: &mut BTreeMap<String, SourceLocation>,
+    attribute: Spanned<SpannedReferences>,
+    to_source_location: &impl Fn(&Location) -> SourceLocation,
+) {
+    match attribute.value {
+        SpannedReferences::Single(target) => {
+            references
+                .entry(target)
+                .or_insert_with(|| to_source_location(&attribute.referenced));
+        }
+        SpannedReferences::List(targets) => {
+            for target in targets {
+                references
+                    .entry(target.value)
+                    .or_insert_with(|| to_source_location(&target.referenced));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn element_and_reference_locations() {
+        let content = "module:\n  name: Test\n\nG1:\n  text: Goal\n  supportedBy: [S1, G2]\n  inContextOf: C1\n";
+        let locations = read_locations("test.yaml", content).unwrap().remove(0);
+        let module = locations.get("module").unwrap();
+        assert_eq!(format!("{}", module.location), "test.yaml:1:1");
+        let g1 = locations.get("G1").unwrap();
+        assert_eq!(format!("{}", g1.location), "test.yaml:4:1");
+        assert_eq!(
+            format!("{}", g1.references.get("S1").unwrap()),
+            "test.yaml:6:17"
+        );
+        assert_eq!(
+            format!("{}", g1.references.get("G2").unwrap()),
+            "test.yaml:6:21"
+        );
+        assert_eq!(
+            format!("{}", g1.references.get("C1").unwrap()),
+            "test.yaml:7:16"
+        );
+    }
+
+    #[test]
+    fn annotation_locations() {
+        let content = "G1:\n  supportedBy: [G2, G3]\n  acp:\n    ACP1: [G2, G3]\n    ACP2: G1\n  multiplicity:\n    G2: 2\n  choice:\n    1 of 2: [G2, G3]\n";
+        let g1 = read_locations("test.yaml", content)
+            .unwrap()
+            .remove(0)
+            .remove("G1")
+            .unwrap();
+        assert_eq!(format!("{}", g1.acp["ACP1"].location), "test.yaml:4:5");
+        assert_eq!(
+            format!("{}", g1.acp["ACP1"].references["G3"]),
+            "test.yaml:4:16"
+        );
+        assert_eq!(
+            format!("{}", g1.acp["ACP2"].references["G1"]),
+            "test.yaml:5:11"
+        );
+        assert_eq!(format!("{}", g1.multiplicity["G2"]), "test.yaml:7:5");
+        assert_eq!(format!("{}", g1.choice["1 of 2"].location), "test.yaml:9:5");
+        assert_eq!(
+            format!("{}", g1.choice["1 of 2"].references["G2"]),
+            "test.yaml:9:14"
+        );
+        assert_eq!(format!("{}", g1.references["G2"]), "test.yaml:2:17");
+    }
+
+    #[test]
+    fn locations_in_several_documents() {
+        let content = "module:\n  name: A\nG1:\n  supportedBy: [S1]\n---\nmodule:\n  name: B\nS1:\n  text: Strategy\n";
+        let locations = read_locations("test.yaml", content).unwrap();
+        assert_eq!(locations.len(), 2);
+        assert_eq!(
+            format!("{}", locations[0]["G1"].references["S1"]),
+            "test.yaml:4:17"
+        );
+        assert_eq!(
+            format!("{}", locations[1]["module"].location),
+            "test.yaml:6:1"
+        );
+        assert_eq!(format!("{}", locations[1]["S1"].location), "test.yaml:8:1");
+    }
+}
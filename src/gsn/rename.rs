@@ -0,0 +1,577 @@
+use super::edit::SourceFile;
+use super::{GsnEdgeType, GsnNode, GsnNodeType, Module, get_node_type_from_text};
+use crate::dirgraph::DirectedGraph;
+use crate::file_utils::is_json_file;
+use anyhow::{Context, Result, anyhow};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+///
+/// Attributes of elements that reference other elements.
+///
+const ELEMENT_REFERENCES: [&str; 8] = [
+    "inContextOf",
+    "supportedBy",
+    "challenges",
+    "defeatedRelation",
+    "optional",
+    "acp",
+    "choice",
+    "multiplicity",
+];
+
+///
+/// Attributes of the module information that reference elements.
+///
+const MODULE_REFERENCES: [&str; 4] = ["extends", "interface", "roots", "contract"];
+
+///
+/// A file in which an element was renamed.
+///
+#[derive(Debug, PartialEq)]
+pub struct Renaming {
+    pub file: String,
+    pub occurrences: usize,
+}
+
+///
+/// Check if element `old` can be renamed to `new`.
+///
+/// The new ID must neither be used by an element nor a module,
+/// and it must not change the type of the element implied by its prefix.
+///
+pub fn check_rename(
+    nodes: &BTreeMap<String, GsnNode>,
+    modules: &BTreeMap<String, Module>,
+    old: &str,
+    new: &str,
+) -> Result<()> {
+    if !nodes.contains_key(old) {
+        Err(anyhow!("Element {old} does not exist."))
+    } else if nodes.contains_key(new) || modules.contains_key(new) || new == "module" {
+        Err(anyhow!(
+            "Cannot rename {old} to {new}, since {new} is already used."
+        ))
+    } else if get_node_type_from_text(old) != get_node_type_from_text(new) {
+        let describe = |id| {
+            get_node_type_from_text(id)
+                .map(|t| t.to_string())
+                .unwrap_or_else(|| "unknown".to_owned())
+        };
+        Err(anyhow!(
+            "Cannot rename {old} to {new}, since the type implied by the ID would change from {} to {}.",
+            describe(old),
+            describe(new)
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+///
+/// Rename element `old` to `new` in all files of `modules`.
+///
+/// The files are modified in place. Returns the files that have been changed.
+///
+pub fn rename_element(
+    nodes: &BTreeMap<String, GsnNode>,
+    modules: &BTreeMap<String, Module>,
+    old: &str,
+    new: &str,
+) -> Result<Vec<Renaming>> {
+    check_rename(nodes, modules, old, new)?;
+    rename_elements(modules, &BTreeMap::from([(old, new)]))
+}
+
+///
+/// Rename elements according to `renames` (old to new ID) in all files of `modules`.
+///
+/// The files are modified in place. Returns the files that have been changed.
+/// The edited files are parsed again and compared to the original ones before any file is written.
+/// If a file could not be edited as expected, no file is modified.
+///
+pub fn rename_elements(
+    modules: &BTreeMap<String, Module>,
+    renames: &BTreeMap<&str, &str>,
+) -> Result<Vec<Renaming>> {
+    let files = modules
+        .values()
+        .map(|m| m.orig_file_name.as_str())
+        .filter(|f| !f.is_empty())
+        .collect::<BTreeSet<_>>();
+    let mut edited_files = vec![];
+    for file in files {
+        let original = std::fs::read_to_string(file)
+            .with_context(|| format!("Failed to open file {file}."))?;
+        let (content, occurrences) = if is_json_file(file) {
+            rename_in_json(&original, renames)
+        } else {
+            let mut source = SourceFile::parse(&original);
+            let occurrences = rename_in_source(&mut source, renames);
+            (source.to_string(), occurrences)
+        };
+        verify_renaming(&original, &content, renames).map_err(|entry| {
+            anyhow!(
+                "Cannot rename elements in file {file}, since {entry} could not be edited. Only elements written as block mappings are supported. No file was modified."
+            )
+        })?;
+        if occurrences > 0 {
+            edited_files.push((file, content, occurrences));
+        }
+    }
+    let mut renamings = vec![];
+    for (file, content, occurrences) in edited_files {
+        std::fs::write(file, content).with_context(|| format!("Failed to write file {file}."))?;
+        renamings.push(Renaming {
+            file: file.to_owned(),
+            occurrences,
+        });
+    }
+    Ok(renamings)
+}
+
+///
+/// Compute new IDs for the elements of `module`.
+///
+/// The elements are numbered per type prefix in the order of their rank, i.e., as they are laid out.
+/// Numbers that are used by elements of other modules are skipped.
+/// Elements whose ID does not start with a known prefix keep their ID.
+/// Returns the mapping from old to new IDs in the order of numbering. Unchanged IDs are omitted.
+///
+pub fn get_renumbering(
+    nodes: &BTreeMap<String, GsnNode>,
+    modules: &BTreeMap<String, Module>,
+    module: &str,
+) -> Result<Vec<(String, String)>> {
+    if !modules.contains_key(module) {
+        return Err(anyhow!("Module {module} does not exist."));
+    }
+    let edges: BTreeMap<String, Vec<(String, GsnEdgeType)>> = nodes
+        .iter()
+        .map(|(id, node)| (id.to_owned(), node.get_edges()))
+        .collect();
+    let graph = DirectedGraph::new(nodes, &edges);
+    let ranks = graph.rank_nodes();
+    let is_renumbered = |id: &str| {
+        nodes
+            .get(id)
+            .is_some_and(|n| n.module == module && n.node_type != Some(GsnNodeType::Module))
+            && get_id_prefix(id).is_some()
+    };
+    let mut order = ranks
+        .iter()
+        .flatten()
+        .flatten()
+        .copied()
+        .filter(|&id| is_renumbered(id))
+        .collect::<Vec<_>>();
+    // Elements that are not ranked keep their relative order
+    let ranked = order.iter().copied().collect::<BTreeSet<_>>();
+    order.extend(
+        nodes
+            .keys()
+            .map(String::as_str)
+            .filter(|&id| is_renumbered(id) && !ranked.contains(id)),
+    );
+    let used = nodes
+        .keys()
+        .map(String::as_str)
+        .filter(|&id| !is_renumbered(id))
+        .chain(modules.keys().map(String::as_str))
+        .collect::<BTreeSet<_>>();
+    let mut counters: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut mapping = vec![];
+    for id in order {
+        // unwrap ok, since only elements with a prefix are renumbered
+        let prefix = get_id_prefix(id).unwrap();
+        let counter = counters.entry(prefix).or_default();
+        let new_id = loop {
+            *counter += 1;
+            let candidate = format!("{prefix}{counter}");
+            if !used.contains(candidate.as_str()) {
+                break candidate;
+            }
+        };
+        if new_id != id {
+            mapping.push((id.to_owned(), new_id));
+        }
+    }
+    Ok(mapping)
+}
+
+///
+/// Get the prefix of `id` that implies the type of the element.
+///
+fn get_id_prefix(id: &str) -> Option<&'static str> {
+    match get_node_type_from_text(id)? {
+        GsnNodeType::Goal => Some("G"),
+        GsnNodeType::Strategy => Some("S"),
+        GsnNodeType::Solution => Some("Sn"),
+        GsnNodeType::Justification => Some("J"),
+        GsnNodeType::Context => Some("C"),
+        GsnNodeType::Assumption => Some("A"),
+        GsnNodeType::CounterGoal => Some("CG"),
+        GsnNodeType::CounterSolution => Some("CSn"),
+        GsnNodeType::Module => None,
+    }
+}
+
+///
+/// Rename elements according to `renames` in `source`.
+/// Returns the number of replaced occurrences.
+///
+fn rename_in_source(source: &mut SourceFile, renames: &BTreeMap<&str, &str>) -> usize {
+    let mut occurrences = 0;
+    for entry in source.entries.iter_mut() {
+        if let Some(new) = renames.get(entry.key.as_str()) {
+            entry.rename(new);
+            occurrences += 1;
+        }
+        let attributes = if entry.key == "module" {
+            &MODULE_REFERENCES[..]
+        } else {
+            &ELEMENT_REFERENCES[..]
+        };
+        for attribute in entry
+            .attributes
+            .iter_mut()
+            .filter(|a| attributes.contains(&a.key.as_str()))
+        {
+            occurrences += attribute.replace_scalars(renames);
+        }
+    }
+    occurrences
+}
+
+///
+/// Check that `edited` contains the documents of `original` with the elements renamed according to `renames`.
+/// Returns a description of the first entry that was not edited as expected.
+///
+fn verify_renaming(
+    original: &str,
+    edited: &str,
+    renames: &BTreeMap<&str, &str>,
+) -> Result<(), String> {
+    let whole_file = || "the file".to_owned();
+    let original = serde_saphyr::from_multiple::<Value>(original).map_err(|_| whole_file())?;
+    let edited = serde_saphyr::from_multiple::<Value>(edited).map_err(|_| whole_file())?;
+    if original.len() != edited.len() {
+        return Err(whole_file());
+    }
+    for (original, edited) in original.iter().zip(&edited) {
+        let (Value::Object(original), Value::Object(edited)) = (original, edited) else {
+            continue;
+        };
+        for (key, value) in original {
+            let (new_key, attributes) = if key == "module" {
+                (key.as_str(), &MODULE_REFERENCES[..])
+            } else {
+                (
+                    renames.get(key.as_str()).copied().unwrap_or(key),
+                    &ELEMENT_REFERENCES[..],
+                )
+            };
+            let mut expected = value.clone();
+            if let Value::Object(entry) = &mut expected {
+                for (_, reference) in entry
+                    .iter_mut()
+                    .filter(|(a, _)| attributes.contains(&a.as_str()))
+                {
+                    *reference = rename_value(reference, renames);
+                }
+            }
+            if edited.get(new_key) != Some(&expected) {
+                return Err(if key == "module" {
+                    "the module information".to_owned()
+                } else {
+                    format!("element {key}")
+                });
+            }
+        }
+        if original.len() != edited.len() {
+            return Err(whole_file());
+        }
+    }
+    Ok(())
+}
+
+///
+/// Rename all strings (incl. the words of relations) and keys in `value` according to `renames`.
+///
+fn rename_value(value: &Value, renames: &BTreeMap<&str, &str>) -> Value {
+    let rename_words = |s: &str| {
+        s.split(' ')
+            .map(|w| renames.get(w).copied().unwrap_or(w))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    match value {
+        Value::String(s) => Value::String(rename_words(s)),
+        Value::Array(items) => {
+            Value::Array(items.iter().map(|i| rename_value(i, renames)).collect())
+        }
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (rename_words(k), rename_value(v, renames)))
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
+}
+
+///
+/// Containers of a JSON document while it is scanned.
+///
+enum JsonFrame {
+    Object {
+        key: Option<String>,
+        expects_key: bool,
+    },
+    Array,
+}
+
+///
+/// Rename elements according to `renames` in the JSON document `content`.
+/// Only the string tokens that are changed are rewritten, the layout of the document is kept.
+/// Returns the new document and the number of replaced occurrences.
+///
+fn rename_in_json(content: &str, renames: &BTreeMap<&str, &str>) -> (String, usize) {
+    let mut result = String::with_capacity(content.len());
+    let mut occurrences = 0;
+    let mut stack: Vec<JsonFrame> = vec![];
+    let mut chars = content.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '{' => stack.push(JsonFrame::Object {
+                key: None,
+                expects_key: true,
+            }),
+            '[' => stack.push(JsonFrame::Array),
+            '}' | ']' => {
+                stack.pop();
+            }
+            ':' | ',' => {
+                if let Some(JsonFrame::Object { key, expects_key }) = stack.last_mut() {
+                    *expects_key = c == ',';
+                    if c == ',' {
+                        *key = None;
+                    }
+                }
+            }
+            '"' => {
+                let mut end = content.len();
+                while let Some((i, c)) = chars.next() {
+                    if c == '\\' {
+                        chars.next();
+                    } else if c == '"' {
+                        end = i + 1;
+                        break;
+                    }
+                }
+                let token = &content[start..end];
+                let value = serde_json::from_str::<String>(token).unwrap_or_default();
+                let is_key = matches!(
+                    stack.last(),
+                    Some(JsonFrame::Object {
+                        expects_key: true,
+                        ..
+                    })
+                );
+                let path = stack
+                    .iter()
+                    .filter_map(|f| match f {
+                        JsonFrame::Object { key, .. } => key.as_deref(),
+                        JsonFrame::Array => None,
+                    })
+                    .collect::<Vec<_>>();
+                let replaced = match path.as_slice() {
+                    // Element ID
+                    [] if is_key && value != "module" => {
+                        renames.get(value.as_str()).map(|new| (new.to_string(), 1))
+                    }
+                    // Reference, incl. nested keys and relations
+                    [id, attribute, ..]
+                        if (*id == "module" && MODULE_REFERENCES.contains(attribute))
+                            || (*id != "module" && ELEMENT_REFERENCES.contains(attribute)) =>
+                    {
+                        let mut count = 0;
+                        let words = value
+                            .split(' ')
+                            .map(|w| match renames.get(w) {
+                                Some(new) => {
+                                    count += 1;
+                                    *new
+                                }
+                                None => w,
+                            })
+                            .collect::<Vec<_>>();
+                        (count > 0).then(|| (words.join(" "), count))
+                    }
+                    _ => None,
+                };
+                match replaced {
+                    Some((new, count)) => {
+                        // unwrap ok, since a string can always be serialized
+                        result.push_str(&serde_json::to_string(&new).unwrap());
+                        occurrences += count;
+                    }
+                    None => result.push_str(token),
+                }
+                if is_key && let Some(JsonFrame::Object { key, .. }) = stack.last_mut() {
+                    *key = Some(value);
+                }
+                continue;
+            }
+            _ => (),
+        }
+        result.push(c);
+    }
+    (result, occurrences)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rename_references() {
+        let mut source = SourceFile::parse(
+            "module:\n  name: M\n  interface: [G1]\n  extends:\n    - module: Other\n      develops:\n        G9: [G1]\n\nG1:\n  text: G1 is the goal\n  supportedBy: S1\n\nCG1:\n  challenges: G1 -> S1\n  acp:\n    ACP1: G1\n",
+        );
+        assert_eq!(
+            rename_in_source(&mut source, &BTreeMap::from([("G1", "G2")])),
+            5
+        );
+        assert_eq!(
+            source.to_string(),
+            "module:\n  name: M\n  interface: [G2]\n  extends:\n    - module: Other\n      develops:\n        G9: [G2]\n\nG2:\n  text: G1 is the goal\n  supportedBy: S1\n\nCG1:\n  challenges: G2 -> S1\n  acp:\n    ACP1: G2\n"
+        );
+    }
+
+    #[test]
+    fn rename_in_json_document() {
+        let content = "{\n  \"module\": {\"name\": \"G1\", \"interface\": [\"G1\"]},\n  \"G1\": {\n    \"text\": \"G1\",\n    \"supportedBy\": [\"S1\"]\n  },\n  \"CG1\": {\"challenges\": \"G1 -> S1\", \"multiplicity\": {\"G1\": \"n\"}}\n}\n";
+        assert_eq!(
+            rename_in_json(content, &BTreeMap::from([("G1", "G2")])),
+            (
+                "{\n  \"module\": {\"name\": \"G1\", \"interface\": [\"G2\"]},\n  \"G2\": {\n    \"text\": \"G1\",\n    \"supportedBy\": [\"S1\"]\n  },\n  \"CG1\": {\"challenges\": \"G2 -> S1\", \"multiplicity\": {\"G2\": \"n\"}}\n}\n"
+                    .to_owned(),
+                4
+            )
+        );
+    }
+
+    #[test]
+    fn swap_ids() {
+        let mut source = SourceFile::parse("G1:\n  supportedBy: [G2]\n\nG2:\n  text: Goal\n");
+        assert_eq!(
+            rename_in_source(&mut source, &BTreeMap::from([("G1", "G2"), ("G2", "G1")])),
+            3
+        );
+        assert_eq!(
+            source.to_string(),
+            "G2:\n  supportedBy: [G1]\n\nG1:\n  text: Goal\n"
+        );
+    }
+
+    #[test]
+    fn verify_flow_style_elements() {
+        let renames = BTreeMap::from([("G2", "G3")]);
+        let original = "G1:\n  supportedBy: [S1]\n\nS1: {text: Strategy G2, supportedBy: [G2]}\n\nG2:\n  text: Goal\n";
+        let mut source = SourceFile::parse(original);
+        rename_in_source(&mut source, &renames);
+        assert_eq!(
+            verify_renaming(original, &source.to_string(), &renames),
+            Err("element S1".to_owned())
+        );
+        assert_eq!(
+            verify_renaming(
+                original,
+                "G1:\n  supportedBy: [S1]\n\nS1: {text: Strategy G2, supportedBy: [G3]}\n\nG3:\n  text: Goal\n",
+                &renames
+            ),
+            Ok(())
+        );
+        let original = "G1:\n  supportedBy: [S1]\n\nS1: {text: Strategy}\n";
+        let renames = BTreeMap::from([("S1", "S2")]);
+        let mut source = SourceFile::parse(original);
+        assert_eq!(rename_in_source(&mut source, &renames), 2);
+        assert_eq!(
+            verify_renaming(original, &source.to_string(), &renames),
+            Ok(())
+        );
+    }
+
+    fn node(module: &str, supported_by: &[&str], in_context_of: &[&str]) -> GsnNode {
+        GsnNode {
+            module: module.to_owned(),
+            supported_by: supported_by.iter().map(|s| s.to_string()).collect(),
+            in_context_of: in_context_of.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn renumber_in_rank_order() -> Result<()> {
+        let nodes = BTreeMap::from([
+            ("G10".to_owned(), node("M", &["S5"], &["C_scope"])),
+            ("S5".to_owned(), node("M", &["G2", "G7", "G9"], &[])),
+            ("G2".to_owned(), node("M", &["Sn3"], &[])),
+            ("G7".to_owned(), node("M", &["Sn1"], &[])),
+            ("G9".to_owned(), node("M", &["X1"], &[])),
+            ("Sn1".to_owned(), node("M", &[], &[])),
+            ("Sn3".to_owned(), node("M", &[], &[])),
+            ("X1".to_owned(), node("M", &[], &[])),
+            ("C_scope".to_owned(), node("M", &[], &[])),
+            ("G1".to_owned(), node("Other", &[], &[])),
+        ]);
+        let modules = BTreeMap::from([
+            ("M".to_owned(), Module::default()),
+            ("Other".to_owned(), Module::default()),
+        ]);
+        assert_eq!(
+            get_renumbering(&nodes, &modules, "M")?,
+            [
+                ("G10", "G2"),
+                ("C_scope", "C1"),
+                ("S5", "S1"),
+                ("G2", "G3"),
+                ("G7", "G4"),
+                ("G9", "G5"),
+                ("Sn3", "Sn1"),
+                ("Sn1", "Sn2"),
+            ]
+            .map(|(o, n)| (o.to_owned(), n.to_owned()))
+        );
+        assert!(get_renumbering(&nodes, &modules, "Unknown").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn refuse_rename() {
+        let nodes = BTreeMap::from([
+            ("G1".to_owned(), GsnNode::default()),
+            ("G2".to_owned(), GsnNode::default()),
+        ]);
+        let modules = BTreeMap::new();
+        assert!(check_rename(&nodes, &modules, "G1", "G3").is_ok());
+        assert_eq!(
+            check_rename(&nodes, &modules, "G3", "G4")
+                .unwrap_err()
+                .to_string(),
+            "Element G3 does not exist."
+        );
+        assert_eq!(
+            check_rename(&nodes, &modules, "G1", "G2")
+                .unwrap_err()
+                .to_string(),
+            "Cannot rename G1 to G2, since G2 is already used."
+        );
+        assert_eq!(
+            check_rename(&nodes, &modules, "G1", "S1")
+                .unwrap_err()
+                .to_string(),
+            "Cannot rename G1 to S1, since the type implied by the ID would change from Goal to Strategy."
+        );
+    }
+}
@@ -0,0 +1,441 @@
+use serde_json::{Value, json};
+
+///
+/// JSON Schema (draft 2020-12) of the input format.
+///
+/// The schema must be kept in sync with the serde definitions of
+/// `GsnNode`, `ModuleInformation`, `ExtendsModule`, `Contract` and `HorizontalIndex`.
+/// Additional attributes are allowed for elements and module information, since they are
+/// kept as additional information.
+///
+pub fn json_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "gsn2x input file",
+        "description": "Goal Structuring Notation in YAML format as read by gsn2x.",
+        "type": "object",
+        "properties": {
+            "module": { "$ref": "#/$defs/ModuleInformation" }
+        },
+        "additionalProperties": { "$ref": "#/$defs/GsnNode" },
+        "$defs": {
+            "StringOrList": {
+                "description": "A single string or a list of strings.",
+                "anyOf": [
+                    { "type": "string" },
+                    { "type": "array", "items": { "type": "string" } }
+                ]
+            },
+            "References": {
+                "description": "A single element ID or a list of element IDs.",
+                "$ref": "#/$defs/StringOrList"
+            },
+            "ReferenceMap": {
+                "description": "Map of element ID to a single element ID or a list of element IDs.",
+                "type": "object",
+                "additionalProperties": { "$ref": "#/$defs/StringOrList" }
+            },
+            "HorizontalIndex": {
+                "description": "Either a relative or an absolute horizontal position.",
+                "type": "object",
+                "properties": {
+                    "relative": { "type": "integer" },
+                    "absolute": {
+                        "anyOf": [
+                            { "type": "integer", "minimum": 0 },
+                            { "enum": ["last"] }
+                        ]
+                    }
+                },
+                "additionalProperties": false,
+                "minProperties": 1,
+                "maxProperties": 1
+            },
+            "NodeType": {
+                "enum": [
+                    "Goal",
+                    "Strategy",
+                    "Solution",
+                    "Justification",
+                    "Context",
+                    "Assumption",
+                    "CounterGoal",
+                    "CounterSolution"
+                ]
+            },
+            "GsnNode": {
+                "description": "A GSN element.",
+                "type": "object",
+                "properties": {
+                    "text": { "type": "string" },
+                    "inContextOf": { "$ref": "#/$defs/References" },
+                    "supportedBy": { "$ref": "#/$defs/References" },
+                    "challenges": {
+                        "description": "An element ID, or a relation in the form of X -> Y.",
+                        "type": "string"
+                    },
+                    "undeveloped": { "type": "boolean" },
+                    "uninstantiated": { "type": "boolean" },
+                    "public": { "type": "boolean" },
+                    "suppress": {
+                        "description": "IDs of checks to suppress for this element.",
+                        "$ref": "#/$defs/StringOrList"
+                    },
+                    "defeated": { "type": "boolean" },
+                    "defeatedRelation": { "$ref": "#/$defs/References" },
+                    "classes": { "type": "array", "items": { "type": "string" } },
+                    "url": { "type": "string" },
+                    "rankIncrement": { "type": "integer", "minimum": 0 },
+                    "horizontalIndex": { "$ref": "#/$defs/HorizontalIndex" },
+                    "nodeType": { "$ref": "#/$defs/NodeType" },
+                    "charWrap": { "type": "integer", "minimum": 0 },
+                    "acp": {
+                        "description": "Assurance claim points: map of ACP name to element IDs.",
+                        "$ref": "#/$defs/ReferenceMap"
+                    },
+                    "multiplicity": {
+                        "description": "Map of element ID to the label of the multiplicity.",
+                        "type": "object",
+                        "additionalProperties": { "type": ["string", "number"] }
+                    },
+                    "optional": { "$ref": "#/$defs/References" },
+                    "choice": {
+                        "description": "Map of choice label to the alternative element IDs.",
+                        "$ref": "#/$defs/ReferenceMap"
+                    }
+                }
+            },
+            "ExtendsModule": {
+                "type": "object",
+                "properties": {
+                    "module": { "type": "string" },
+                    "develops": {
+                        "type": "object",
+                        "additionalProperties": { "type": "array", "items": { "type": "string" } }
+                    }
+                },
+                "required": ["module"]
+            },
+            "Contract": {
+                "type": "object",
+                "properties": {
+                    "goals": { "$ref": "#/$defs/References" },
+                    "satisfiedBy": { "$ref": "#/$defs/References" },
+                    "inContextOf": { "$ref": "#/$defs/References" }
+                },
+                "required": ["goals", "satisfiedBy"]
+            },
+            "ModuleInformation": {
+                "description": "Information about the module.",
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" },
+                    "brief": { "type": "string" },
+                    "extends": { "type": "array", "items": { "$ref": "#/$defs/ExtendsModule" } },
+                    "horizontalIndex": { "$ref": "#/$defs/HorizontalIndex" },
+                    "rankIncrement": { "type": "integer", "minimum": 0 },
+                    "charWrap": { "type": "integer", "minimum": 0 },
+                    "siblingOrder": {
+                        "description": "Order of elements on the same rank.",
+                        "enum": ["lexicographic", "natural", "authored"]
+                    },
+                    "stylesheets": { "type": "array", "items": { "type": "string" } },
+                    "uses": { "type": "array", "items": { "type": "string" } },
+                    "interface": { "type": "array", "items": { "type": "string" } },
+                    "roots": {
+                        "description": "Root elements of the module. Several roots form a forest.",
+                        "type": "array",
+                        "items": { "type": "string" }
+                    },
+                    "contract": { "type": "array", "items": { "$ref": "#/$defs/Contract" } },
+                    "suppress": {
+                        "description": "IDs of checks to suppress for all elements of this module.",
+                        "$ref": "#/$defs/StringOrList"
+                    }
+                },
+                "required": ["name"]
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::gsn::{
+        AbsoluteIndex, Challenge, Contract, ELEMENT_ATTRIBUTES, ExtendsModule, GsnDocument,
+        GsnNode, GsnNodeType, HorizontalIndex, MODULE_ATTRIBUTES, ModuleInformation, SiblingOrder,
+    };
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::path::Path;
+
+    ///
+    /// Minimal validator supporting the parts of JSON Schema used above.
+    ///
+    fn validate(schema: &Value, root: &Value, value: &Value) -> bool {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let target = reference
+                .trim_start_matches("#/")
+                .split('/')
+                .fold(root, |s, part| &s[part]);
+            if !validate(target, root, value) {
+                return false;
+            }
+        }
+        if let Some(any_of) = schema.get("anyOf").and_then(Value::as_array)
+            && !any_of.iter().any(|s| validate(s, root, value))
+        {
+            return false;
+        }
+        if let Some(options) = schema.get("enum").and_then(Value::as_array)
+            && !options.contains(value)
+        {
+            return false;
+        }
+        if let Some(types) = schema.get("type") {
+            let types = match types {
+                Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+                _ => vec![types.as_str().unwrap()],
+            };
+            let type_ok = types.iter().any(|t| match *t {
+                "string" => value.is_string(),
+                "integer" => value.is_i64() || value.is_u64(),
+                "number" => value.is_number(),
+                "boolean" => value.is_boolean(),
+                "array" => value.is_array(),
+                "object" => value.is_object(),
+                _ => false,
+            });
+            if !type_ok {
+                return false;
+            }
+        }
+        if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64)
+            && value.as_f64().is_some_and(|v| v < minimum)
+        {
+            return false;
+        }
+        if let Some(items) = schema.get("items")
+            && let Some(array) = value.as_array()
+            && !array.iter().all(|v| validate(items, root, v))
+        {
+            return false;
+        }
+        if let Some(object) = value.as_object() {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (key, v) in object {
+                let ok = match properties.and_then(|p| p.get(key)) {
+                    Some(s) => validate(s, root, v),
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(allowed)) => *allowed,
+                        Some(s) => validate(s, root, v),
+                        None => true,
+                    },
+                };
+                if !ok {
+                    return false;
+                }
+            }
+            if let Some(required) = schema.get("required").and_then(Value::as_array)
+                && !required
+                    .iter()
+                    .all(|r| object.contains_key(r.as_str().unwrap()))
+            {
+                return false;
+            }
+            if schema
+                .get("minProperties")
+                .and_then(Value::as_u64)
+                .is_some_and(|m| (object.len() as u64) < m)
+                || schema
+                    .get("maxProperties")
+                    .and_then(Value::as_u64)
+                    .is_some_and(|m| (object.len() as u64) > m)
+            {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn collect_yaml_files(dir: &Path, files: &mut Vec<std::path::PathBuf>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                collect_yaml_files(&path, files);
+            } else if path.extension().is_some_and(|e| e == "yaml")
+                // Bindings for pattern instantiation have a different format.
+                && !path.to_string_lossy().ends_with(".binding.yaml")
+            {
+                files.push(path);
+            }
+        }
+    }
+
+    fn schema_properties(schema: &Value, definition: &str) -> BTreeSet<String> {
+        schema["$defs"][definition]["properties"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    fn serialized_keys(value: impl serde::Serialize) -> BTreeSet<String> {
+        serde_json::to_value(value)
+            .unwrap()
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn schema_in_sync_with_serde() {
+        let schema = json_schema();
+        let node = GsnNode {
+            text: "text".to_owned(),
+            in_context_of: vec!["C1".to_owned()],
+            supported_by: vec!["G2".to_owned()],
+            challenges: Some(Challenge::Node("G3".to_owned())),
+            undeveloped: true,
+            uninstantiated: true,
+            public: true,
+            suppress: vec!["C01".to_owned()],
+            defeated: true,
+            defeated_relation: vec!["G2".to_owned()],
+            classes: vec!["class".to_owned()],
+            url: Some("url".to_owned()),
+            rank_increment: Some(1),
+            horizontal_index: Some(HorizontalIndex::Relative(1)),
+            node_type: Some(GsnNodeType::Goal),
+            char_wrap: Some(1),
+            acp: BTreeMap::from([("ACP1".to_owned(), vec!["G2".to_owned()])]),
+            multiplicity: BTreeMap::from([("G2".to_owned(), "n".to_owned())]),
+            optional: vec!["G2".to_owned()],
+            choice: BTreeMap::from([("1".to_owned(), vec!["G2".to_owned()])]),
+            ..Default::default()
+        };
+        assert_eq!(
+            serialized_keys(&node),
+            schema_properties(&schema, "GsnNode")
+        );
+        let contract = Contract {
+            goals: vec!["G1".to_owned()],
+            satisfied_by: vec!["G2".to_owned()],
+            in_context_of: vec!["C1".to_owned()],
+        };
+        assert_eq!(
+            serialized_keys(&contract),
+            schema_properties(&schema, "Contract")
+        );
+        let extends = ExtendsModule {
+            module: "M".to_owned(),
+            develops: BTreeMap::from([("G1".to_owned(), vec!["G2".to_owned()])]),
+        };
+        assert_eq!(
+            serialized_keys(&extends),
+            schema_properties(&schema, "ExtendsModule")
+        );
+        let module = ModuleInformation {
+            brief: Some("brief".to_owned()),
+            extends: vec![extends],
+            horizontal_index: Some(HorizontalIndex::Absolute(AbsoluteIndex::Last)),
+            rank_increment: Some(1),
+            char_wrap: Some(1),
+            sibling_order: Some(SiblingOrder::Natural),
+            stylesheets: vec!["css".to_owned()],
+            uses: vec!["file".to_owned()],
+            interface: vec!["G1".to_owned()],
+            roots: vec!["G1".to_owned()],
+            contract: vec![contract],
+            suppress: vec!["C01".to_owned()],
+            ..ModuleInformation::new("M".to_owned())
+        };
+        assert_eq!(
+            serialized_keys(&module),
+            schema_properties(&schema, "ModuleInformation")
+        );
+    }
+
+    #[test]
+    fn reserved_attributes_in_sync_with_schema() {
+        let schema = json_schema();
+        assert_eq!(
+            ELEMENT_ATTRIBUTES
+                .iter()
+                .map(|&a| a.to_owned())
+                .collect::<BTreeSet<_>>(),
+            schema_properties(&schema, "GsnNode")
+        );
+        assert_eq!(
+            MODULE_ATTRIBUTES
+                .iter()
+                .map(|&a| a.to_owned())
+                .collect::<BTreeSet<_>>(),
+            schema_properties(&schema, "ModuleInformation")
+        );
+    }
+
+    #[test]
+    fn horizontal_index_in_schema() {
+        let schema = json_schema();
+        let index = &schema["$defs"]["HorizontalIndex"];
+        for valid in [
+            HorizontalIndex::Relative(-1),
+            HorizontalIndex::Absolute(AbsoluteIndex::Number(2)),
+            HorizontalIndex::Absolute(AbsoluteIndex::Last),
+        ] {
+            assert!(validate(
+                index,
+                &schema,
+                &serde_json::to_value(valid).unwrap()
+            ));
+        }
+        assert!(!validate(index, &schema, &json!({"absolute": "first"})));
+        assert!(!validate(index, &schema, &json!({})));
+    }
+
+    #[test]
+    fn inputs_valid_against_schema() {
+        let schema = json_schema();
+        let mut files = vec![];
+        collect_yaml_files(Path::new("examples"), &mut files);
+        collect_yaml_files(Path::new("tests"), &mut files);
+        let mut checked = 0;
+        for file in files {
+            let content = std::fs::read_to_string(&file).unwrap();
+            // Only files accepted by gsn2x are expected to be valid
+            if serde_saphyr::from_str::<BTreeMap<String, GsnDocument>>(&content).is_err() {
+                continue;
+            }
+            let value: Value = serde_saphyr::from_str(&content).unwrap();
+            // Empty files contain no elements and are reported by gsn2x anyway
+            if value.is_null() {
+                continue;
+            }
+            assert!(
+                validate(&schema, &schema, &value),
+                "{} is not valid against the schema",
+                file.display()
+            );
+            checked += 1;
+        }
+        assert!(checked > 50);
+    }
+
+    #[test]
+    fn invalid_input_rejected_by_schema() {
+        let schema = json_schema();
+        for invalid in [
+            json!(["X1"]),
+            json!({"module": {"brief": "No name"}}),
+            json!({"G1": {"supportedBy": [1]}}),
+            json!({"G1": {"nodeType": "Unknown"}}),
+            json!({"G1": {"undeveloped": "yes"}}),
+        ] {
+            assert!(!validate(&schema, &schema, &invalid), "{invalid}");
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use super::{Challenge, GsnNode, GsnNodeType, Module, get_node_type_from_text};
+use super::{
+    Challenge, ELEMENT_ATTRIBUTES, GsnNode, GsnNodeType, MODULE_ATTRIBUTES, Module,
+    find_misspelled_attribute, get_node_type_from_text,
+};
 use crate::diagnostics::Diagnostics;
 use std::collections::{BTreeMap, HashSet};
 
@@ -13,30 +16,100 @@ pub fn validate_module(
     nodes: &BTreeMap<String, GsnNode>,
     extended_check: bool,
     warn_dialectic: bool,
+    layers: &[&str],
 ) -> Result<(), ()> {
-    let all_results = nodes
-        .iter()
-        .filter(|(_, n)| n.module == module_name)
-        .flat_map(|(id, node)| {
-            [
-                // Validate that type of node is known
-                validate_type(diag, module_name, id, node),
-                // Validate if id and type do not contradict
-                validate_id(diag, module_name, id, node, extended_check),
-                // Validate all references of node
-                validate_references(diag, module_name, nodes, id, node),
-                // Validate all assurance claim points
-                validate_assurance_claim_point(diag, module_name, nodes, id, node),
-                // Validate if defeated is correctly set
-                validate_defeated(diag, module_name, nodes, id, node),
-            ]
+    // Only errors that are counted stop processing.
+    let failed = diag
+        .check(|diag| {
+            let all_results = nodes
+                .iter()
+                // Module references are created by gsn2x and thus need no validation
+                .filter(|(_, n)| {
+                    n.module == module_name && n.node_type != Some(GsnNodeType::Module)
+                })
+                .flat_map(|(id, node)| {
+                    [
+                        // Validate that type of node is known
+                        validate_type(diag, module_name, id, node),
+                        // Validate if id and type do not contradict
+                        validate_id(diag, module_name, id, node, extended_check),
+                        // Validate all references of node
+                        validate_references(diag, module_name, nodes, id, node),
+                        // Validate all assurance claim points
+                        validate_assurance_claim_point(diag, module_name, nodes, id, node),
+                        // Validate if defeated is correctly set
+                        validate_defeated(diag, module_name, nodes, id, node),
+                        // Validate multiplicity, optionality and choice of argument patterns
+                        validate_pattern(diag, module_name, id, node),
+                        // Validate that additional attributes are no misspelled attributes
+                        validate_additional_attributes(diag, module_name, id, node, layers),
+                    ]
+                })
+                .collect::<Vec<Result<(), ()>>>();
+            if all_results.contains(&Err(())) {
+                Err(())
+            } else {
+                Ok(())
+            }
         })
-        .collect::<Vec<Result<(), ()>>>();
-    all_results
-        .into_iter()
-        .collect::<Result<Vec<_>, ()>>()
-        .and_then(|_| validate_module_extensions(diag, module_name, nodes, module_info))
-        .and_then(|_| validate_dialectic_extension(diag, module_name, nodes, warn_dialectic))
+        .is_err();
+    validate_module_attributes(diag, module_name, module_info);
+    if failed {
+        return Err(());
+    }
+    diag.check(|d| validate_module_extensions(d, module_name, nodes, module_info))?;
+    diag.check(|d| validate_dialectic_extension(d, module_name, module_info, nodes, warn_dialectic))
+}
+
+///
+/// Validate additional attributes
+///
+/// Additional attributes that are spelled similar to an attribute of an element are likely typos.
+/// They would otherwise be silently treated as layers.
+/// Configured `layers` are intended, thus they are not reported.
+///
+fn validate_additional_attributes(
+    diag: &mut Diagnostics,
+    module: &str,
+    id: &str,
+    node: &GsnNode,
+    layers: &[&str],
+) -> Result<(), ()> {
+    for key in node
+        .additional
+        .keys()
+        .filter(|k| !layers.contains(&k.as_str()))
+    {
+        if let Some(attribute) = find_misspelled_attribute(key, &ELEMENT_ATTRIBUTES) {
+            diag.add_warning_at(
+                Some(module),
+                node.get_location(),
+                &[id],
+                format!("V18: Element {id} has unknown attribute {key}. Did you mean {attribute}?"),
+            );
+        }
+    }
+    Ok(())
+}
+
+///
+/// Validate additional attributes of module information
+///
+/// Same as for elements, see `validate_additional_attributes`.
+///
+fn validate_module_attributes(diag: &mut Diagnostics, module: &str, module_info: &Module) {
+    for key in module_info.meta.additional.keys() {
+        if let Some(attribute) = find_misspelled_attribute(key, &MODULE_ATTRIBUTES) {
+            diag.add_warning_at(
+                Some(module),
+                module_info.location.as_ref(),
+                &[],
+                format!(
+                    "V18: Module information has unknown attribute {key}. Did you mean {attribute}?"
+                ),
+            );
+        }
+    }
 }
 
 ///
@@ -47,8 +120,10 @@ pub fn validate_module(
 ///
 fn validate_type(diag: &mut Diagnostics, module: &str, id: &str, node: &GsnNode) -> Result<(), ()> {
     if node.node_type.is_none() {
-        diag.add_error(
+        diag.add_error_at(
             Some(module),
+            node.get_location(),
+            &[id],
             format!(
                 "V01: Element {id} is of unknown type. Please see documentation for supported types"
             ),
@@ -76,8 +151,10 @@ fn validate_id(
         && let Some(type_from_node) = node.node_type
         && type_from_node != type_from_id
     {
-        diag.add_warning(
+        diag.add_warning_at(
             Some(module),
+            node.get_location(),
+            &[id],
             format!(
                 "V08: Element {id} has type {type_from_node}, but ID indicates type {type_from_id}"
             ),
@@ -137,6 +214,7 @@ fn validate_references(
                 GsnNodeType::Solution,
                 GsnNodeType::CounterSolution,
                 GsnNodeType::Strategy,
+                GsnNodeType::Module,
             ]);
         }
         if node.node_type == Some(GsnNodeType::CounterGoal) {
@@ -155,22 +233,46 @@ fn validate_references(
             "supported by element",
             &valid_ref_types,
         );
+        let own_module = node
+            .supported_by
+            .iter()
+            .filter(|&n| {
+                nodes
+                    .get(n)
+                    .is_some_and(|x| x.node_type == Some(GsnNodeType::Module) && x.module == module)
+            })
+            .try_for_each(|n| {
+                diag.add_error_at(
+                    Some(module),
+                    node.get_reference_location(n),
+                    &[id, n.as_str()],
+                    format!("V17: Element {id} is supported by its own module {n}."),
+                );
+                Err(())
+            });
         let devundev = if node.undeveloped {
-            diag.add_error(
+            diag.add_error_at(
                 Some(module),
+                node.get_location(),
+                &[id],
                 format!("V03: Undeveloped element {id} has supporting arguments."),
             );
             Err(())
         } else {
             Ok(())
         };
-        valid_refs.and(devundev)
+        valid_refs.and(own_module).and(devundev)
     } else if (node.node_type == Some(GsnNodeType::Strategy)
         || node.node_type == Some(GsnNodeType::Goal))
         && !node.undeveloped
     {
         // No "supported by" entries, but Strategy and Goal => undeveloped
-        diag.add_warning(Some(module), format!("V02: Element {id} is undeveloped."));
+        diag.add_warning_at(
+            Some(module),
+            node.get_location(),
+            &[id],
+            format!("V02: Element {id} is undeveloped."),
+        );
         Ok(())
     } else {
         Ok(())
@@ -181,8 +283,10 @@ fn validate_references(
         || node.node_type == Some(GsnNodeType::CounterSolution))
         && node.challenges.is_some()
     {
-        diag.add_error(
+        diag.add_error_at(
             Some(module),
+            node.get_location(),
+            &[id],
             format!(
                 "V12: {id} is not a CounterGoal nor CounterSolution but challenges another element or relation."
             ),
@@ -212,13 +316,16 @@ fn validate_reference(
 ) -> Result<(), ()> {
     // HashSet ok, since order is never important.
     let mut set = HashSet::with_capacity(refs.len());
+    let location = |r: &str| nodes.get(node).and_then(|n| n.get_reference_location(r));
     let valid_references = refs
         .iter()
         .flat_map(|n| {
             [
                 if n == node {
-                    diag.add_error(
+                    diag.add_error_at(
                         Some(module),
+                        location(n),
+                        &[node],
                         format!("V06: Element {node} references itself in {diag_str}."),
                     );
                     Err(())
@@ -227,8 +334,10 @@ fn validate_reference(
                 },
                 {
                     if !set.insert(n) {
-                        diag.add_warning(
+                        diag.add_warning_at(
                             Some(module),
+                            location(n),
+                            &[node, n.as_str()],
                             format!("V05: Element {node} has duplicate entry {n} in {diag_str}."),
                         );
                     }
@@ -236,8 +345,10 @@ fn validate_reference(
                         .iter()
                         .any(|&r| nodes.get(n).map(|x| x.node_type == Some(r)).unwrap_or(true))
                     {
-                        diag.add_error(
+                        diag.add_error_at(
                     Some(module),
+                    location(n),
+                    &[node, n.as_str()],
                     format!("V04: Element {node} has invalid type of reference {n} in {diag_str}."),
                 );
                         Err(())
@@ -280,8 +391,10 @@ fn validate_assurance_claim_point(
     let results = node.acp.iter().flat_map(|(acp, references)|
         references.iter().map(|r| {
             if !&potential_references.contains(&r.as_str()) {
-                diag.add_error(
+                diag.add_error_at(
                     Some(module),
+                    node.get_acp_location(acp, Some(r)),
+                    &[id, r.as_str()],
                     format!("V09: Element {id} has an assurance claim point {acp} that references {r}, but this is neither its own ID nor any of the connected elements."),
                 );
                 Err(())
@@ -310,8 +423,10 @@ fn validate_module_extensions(
                     || local_id.starts_with('S')
                     || local_id.starts_with('G'))
                 {
-                    diag.add_error(
+                    diag.add_error_at(
                             Some(module_name),
+                            module_info.location.as_ref(),
+                            &[local_id.as_str()],
                             format!(
                                 "V07: Element {local_id} is of wrong type. Only Strategies, Goals and Solutions can develop other Goals and Strategies."
                             ),
@@ -322,8 +437,10 @@ fn validate_module_extensions(
                     .filter(|(_, n)| n.module == module_name)
                     .any(|(id, _)| id == local_id)
                 {
-                    diag.add_error(
+                    diag.add_error_at(
                             Some(module_name),
+                            module_info.location.as_ref(),
+                            &[local_id.as_str(), foreign_id.as_str()],
                             format!(
                                 "V07: Element {} in module {} supposed to develop {} in module {} does not exist.",
                                 local_id,
@@ -365,8 +482,10 @@ fn validate_defeated(
             .any(|(_, n)| matches!(&n.challenges, Some(Challenge::Node(n)) if n == id))
     {
         // TODO Amend check: an incoming defeated relation would be good, too????
-        diag.add_error(
+        diag.add_error_at(
             Some(module),
+            node.get_location(),
+            &[id],
             format!("V10: Element {id} is marked as defeated, but no other element challenges it."),
         );
         Err(())
@@ -381,8 +500,10 @@ fn validate_defeated(
             if node.supported_by.contains(rel) || node.in_context_of.contains(rel) || node.challenges == Some(Challenge::Node(rel.to_owned())) {
                 Ok(())
             } else {
-                diag.add_error(
+                diag.add_error_at(
                     Some(module),
+                    node.get_reference_location(rel),
+                    &[id, rel.as_str()],
                     format!("V13: Relation from {id} to {rel} is marked as defeated, but {id} has no relation to {rel}."),
                 );
                 Err(())
@@ -393,12 +514,100 @@ fn validate_defeated(
     node_defeated.and(rel_defeated)
 }
 
+///
+/// Validate the annotations of the Argument Pattern Extension
+///
+/// - Check that multiplicity, optionality and choice only annotate relations of the element.
+/// - Check that a choice offers at least two alternatives.
+/// - Check that a relation is not optional and multiple at the same time (V16).
+/// - Check that a relation is not part of more than one choice (V19).
+///
+fn validate_pattern(
+    diag: &mut Diagnostics,
+    module: &str,
+    id: &str,
+    node: &GsnNode,
+) -> Result<(), ()> {
+    let mut errors = 0;
+    for (kind, target, location) in node
+        .multiplicity
+        .keys()
+        .map(|t| ("multiplicity", t, node.get_multiplicity_location(t)))
+        .chain(
+            node.optional
+                .iter()
+                .map(|t| ("optionality", t, node.get_reference_location(t))),
+        )
+        .chain(node.choice.iter().flat_map(|(choice, alternatives)| {
+            alternatives
+                .iter()
+                .map(|t| ("choice", t, node.get_choice_location(choice, Some(t))))
+        }))
+    {
+        if !(node.supported_by.contains(target) || node.in_context_of.contains(target)) {
+            diag.add_error_at(
+                Some(module),
+                location,
+                &[id, target.as_str()],
+                format!("V14: Element {id} declares {kind} for {target}, but {id} has no relation to {target}."),
+            );
+            errors += 1;
+        }
+    }
+    for (choice, alternatives) in &node.choice {
+        if alternatives.len() < 2 {
+            diag.add_error_at(
+                Some(module),
+                node.get_choice_location(choice, None),
+                &[id],
+                format!(
+                    "V15: Choice {choice} of element {id} does not offer at least two alternatives."
+                ),
+            );
+            errors += 1;
+        }
+    }
+    for target in node
+        .optional
+        .iter()
+        .filter(|&t| node.multiplicity.contains_key(t))
+    {
+        diag.add_error_at(
+            Some(module),
+            node.get_multiplicity_location(target),
+            &[id, target.as_str()],
+            format!(
+                "V16: Relation from {id} to {target} is marked as optional and has a multiplicity."
+            ),
+        );
+        errors += 1;
+    }
+    let mut chosen = HashSet::new();
+    for (choice, target) in node
+        .choice
+        .iter()
+        .flat_map(|(choice, alternatives)| alternatives.iter().map(move |t| (choice, t)))
+    {
+        if !chosen.insert(target) {
+            diag.add_error_at(
+                Some(module),
+                node.get_choice_location(choice, Some(target)),
+                &[id, target.as_str()],
+                format!("V19: Relation from {id} to {target} is part of more than one choice."),
+            );
+            errors += 1;
+        }
+    }
+    if errors == 0 { Ok(()) } else { Err(()) }
+}
+
 ///
 /// Perform check if dialectic extension is used.
 ///
 fn validate_dialectic_extension(
     diag: &mut Diagnostics,
     module: &str,
+    module_info: &Module,
     nodes: &BTreeMap<String, GsnNode>,
     warn_dialectic: bool,
 ) -> Result<(), ()> {
@@ -418,8 +627,13 @@ fn validate_dialectic_extension(
         if dialectic_nodes.is_empty() {
             Ok(())
         } else {
-            diag.add_warning(
+            diag.add_warning_at(
                 Some(module),
+                module_info.location.as_ref(),
+                &dialectic_nodes
+                    .iter()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>(),
                 format!(
                     "V11: Dialectic extension is used. See elements: {}",
                     dialectic_nodes.join(", ")
@@ -436,7 +650,7 @@ fn validate_dialectic_extension(
 mod test {
     use crate::{
         diagnostics::DiagType,
-        gsn::{ExtendsModule, ModuleInformation},
+        gsn::{ExtendsModule, ModuleInformation, SiblingOrder},
     };
 
     use super::*;
@@ -532,7 +746,9 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(validate_module(&mut d, "", &Module::default(), &nodes, false, false).is_err());
+        assert!(
+            validate_module(&mut d, "", &Module::default(), &nodes, false, false, &[]).is_err()
+        );
         assert_eq!(d.messages.len(), 1);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -556,7 +772,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true).is_err());
+        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true, &[]).is_err());
         assert_eq!(d.messages.len(), 2);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -592,14 +808,18 @@ mod test {
                 "",
                 &Module {
                     orig_file_name: "".to_owned(),
+                    document: None,
                     meta: ModuleInformation::default(),
                     origin: crate::gsn::Origin::CommandLine,
                     canonical_path: None,
                     output_path: None,
+                    location: None,
+                    sibling_order: SiblingOrder::default(),
                 },
                 &nodes,
                 true,
                 true,
+                &[],
             )
             .is_err()
         );
@@ -626,7 +846,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true).is_err());
+        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true, &[]).is_err());
         assert_eq!(d.messages.len(), 2);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -657,7 +877,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true).is_err());
+        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true, &[]).is_err());
         assert_eq!(d.messages.len(), 2);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -702,7 +922,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true).is_ok());
+        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true, &[]).is_ok());
         assert_eq!(d.messages.len(), 1);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Warning);
@@ -734,7 +954,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true).is_ok());
+        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true, &[]).is_ok());
         assert_eq!(d.messages.len(), 1);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Warning);
@@ -782,7 +1002,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true).is_err());
+        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true, &[]).is_err());
         assert_eq!(d.messages.len(), 3);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -840,7 +1060,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true).is_err());
+        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true, &[]).is_err());
         assert_eq!(d.messages.len(), 3);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -885,7 +1105,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true).is_err());
+        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true, &[]).is_err());
         assert_eq!(d.messages.len(), 1);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -910,7 +1130,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true).is_err());
+        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true, &[]).is_err());
         assert_eq!(d.messages.len(), 1);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -943,7 +1163,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true).is_err());
+        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true, &[]).is_err());
         assert_eq!(d.messages.len(), 1);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -977,7 +1197,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true).is_ok());
+        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true, &[]).is_ok());
         assert_eq!(d.messages.len(), 1);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Warning);
@@ -1001,7 +1221,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true).is_ok());
+        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true, &[]).is_ok());
         assert_eq!(d.messages.len(), 0);
         assert_eq!(d.errors, 0);
         assert_eq!(d.warnings, 0);
@@ -1026,7 +1246,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true).is_ok());
+        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true, &[]).is_ok());
         assert_eq!(d.messages.len(), 2);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Warning);
@@ -1057,7 +1277,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true).is_ok());
+        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true, &[]).is_ok());
         assert_eq!(d.messages.len(), 2);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Warning);
@@ -1089,7 +1309,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true).is_err());
+        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true, &[]).is_err());
         assert_eq!(d.messages.len(), 1);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -1113,6 +1333,7 @@ mod test {
                 "mod",
                 &Module {
                     orig_file_name: "mod".to_owned(),
+                    document: None,
                     meta: ModuleInformation {
                         name: "mod".to_owned(),
                         brief: Some("brief".to_owned()),
@@ -1121,19 +1342,27 @@ mod test {
                             develops,
                         }],
                         uses: vec![],
+                        interface: vec![],
+                        roots: vec![],
+                        contract: vec![],
+                        suppress: vec![],
                         stylesheets: vec![],
                         horizontal_index: None,
                         rank_increment: None,
                         char_wrap: None,
+                        sibling_order: None,
                         additional: BTreeMap::new(),
                     },
                     origin: crate::gsn::Origin::CommandLine,
                     canonical_path: None,
                     output_path: None,
+                    location: None,
+                    sibling_order: SiblingOrder::default(),
                 },
                 &nodes,
                 true,
                 true,
+                &[],
             )
             .is_err()
         );
@@ -1160,6 +1389,7 @@ mod test {
                 "",
                 &Module {
                     orig_file_name: "".to_owned(),
+                    document: None,
                     meta: ModuleInformation {
                         name: "mod".to_owned(),
                         brief: Some("brief".to_owned()),
@@ -1168,19 +1398,27 @@ mod test {
                             develops,
                         }],
                         uses: vec![],
+                        interface: vec![],
+                        roots: vec![],
+                        contract: vec![],
+                        suppress: vec![],
                         stylesheets: vec![],
                         horizontal_index: None,
                         rank_increment: None,
                         char_wrap: None,
+                        sibling_order: None,
                         additional: BTreeMap::new(),
                     },
                     origin: crate::gsn::Origin::CommandLine,
                     canonical_path: None,
                     output_path: None,
+                    location: None,
+                    sibling_order: SiblingOrder::default(),
                 },
                 &nodes,
                 true,
                 true,
+                &[],
             )
             .is_err()
         );
@@ -1195,6 +1433,149 @@ mod test {
         assert_eq!(d.warnings, 0);
     }
 
+    #[test]
+    fn module_reference() {
+        let mut d = Diagnostics::default();
+        let mut nodes = BTreeMap::<String, GsnNode>::new();
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                supported_by: vec!["Sub".to_owned()],
+                node_type: Some(GsnNodeType::Goal),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "Sub".to_owned(),
+            GsnNode {
+                node_type: Some(GsnNodeType::Module),
+                module: "Sub".to_owned(),
+                ..Default::default()
+            },
+        );
+        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true, &[]).is_ok());
+        assert_eq!(d.messages.len(), 0);
+    }
+
+    #[test]
+    fn own_module_reference() {
+        let mut d = Diagnostics::default();
+        let mut nodes = BTreeMap::<String, GsnNode>::new();
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                supported_by: vec!["Main".to_owned()],
+                node_type: Some(GsnNodeType::Goal),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "Main".to_owned(),
+            GsnNode {
+                node_type: Some(GsnNodeType::Module),
+                ..Default::default()
+            },
+        );
+        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true, &[]).is_err());
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(d.messages[0].module, Some("".to_owned()));
+        assert_eq!(d.messages[0].diag_type, DiagType::Error);
+        assert_eq!(
+            d.messages[0].msg,
+            "V17: Element G1 is supported by its own module Main."
+        );
+        assert_eq!(d.errors, 1);
+        assert_eq!(d.warnings, 0);
+    }
+
+    #[test]
+    fn misspelled_attribute() {
+        let mut d = Diagnostics::default();
+        let mut nodes = BTreeMap::<String, GsnNode>::new();
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                node_type: Some(GsnNodeType::Goal),
+                undeveloped: true,
+                additional: BTreeMap::from([
+                    ("supportBy".to_owned(), "G2".to_owned()),
+                    ("layer1".to_owned(), "Layer".to_owned()),
+                ]),
+                ..Default::default()
+            },
+        );
+        assert!(validate_module(&mut d, "", &Module::default(), &nodes, false, false, &[]).is_ok());
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(d.messages[0].diag_type, DiagType::Warning);
+        assert_eq!(
+            d.messages[0].msg,
+            "V18: Element G1 has unknown attribute supportBy. Did you mean supportedBy?"
+        );
+    }
+
+    #[test]
+    fn layers_are_no_misspelled_attributes() {
+        let mut nodes = BTreeMap::<String, GsnNode>::new();
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                node_type: Some(GsnNodeType::Goal),
+                undeveloped: true,
+                additional: BTreeMap::from([
+                    ("texts".to_owned(), "Layer".to_owned()),
+                    ("context".to_owned(), "Layer".to_owned()),
+                ]),
+                ..Default::default()
+            },
+        );
+        let mut d = Diagnostics::default();
+        assert!(validate_module(&mut d, "", &Module::default(), &nodes, false, false, &[]).is_ok());
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(
+            d.messages[0].msg,
+            "V18: Element G1 has unknown attribute texts. Did you mean text?"
+        );
+        let mut d = Diagnostics::default();
+        assert!(
+            validate_module(
+                &mut d,
+                "",
+                &Module::default(),
+                &nodes,
+                false,
+                false,
+                &["context", "texts"]
+            )
+            .is_ok()
+        );
+        assert_eq!(d.messages.len(), 0);
+    }
+
+    #[test]
+    fn misspelled_module_attribute() {
+        let mut d = Diagnostics::default();
+        let mut nodes = BTreeMap::<String, GsnNode>::new();
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                node_type: Some(GsnNodeType::Goal),
+                undeveloped: true,
+                ..Default::default()
+            },
+        );
+        let mut module = Module::default();
+        module
+            .meta
+            .additional
+            .insert("use".to_owned(), "other.gsn.yaml".to_owned());
+        assert!(validate_module(&mut d, "", &module, &nodes, false, false, &[]).is_ok());
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(
+            d.messages[0].msg,
+            "V18: Module information has unknown attribute use. Did you mean uses?"
+        );
+    }
+
     #[test]
     fn invalid_acp_ref() {
         let mut d = Diagnostics::default();
@@ -1216,7 +1597,7 @@ mod test {
                 ..Default::default()
             },
         );
-        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true).is_err());
+        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true, &[]).is_err());
         assert_eq!(d.messages.len(), 1);
         assert_eq!(d.messages[0].module, Some("".to_owned()));
         assert_eq!(d.messages[0].diag_type, DiagType::Error);
@@ -1227,4 +1608,116 @@ mod test {
         assert_eq!(d.errors, 1);
         assert_eq!(d.warnings, 0);
     }
+
+    #[test]
+    fn pattern_unrelated() {
+        let mut d = Diagnostics::default();
+        let mut nodes = BTreeMap::<String, GsnNode>::new();
+        nodes.insert(
+            "S1".to_owned(),
+            GsnNode {
+                supported_by: vec!["G2".to_owned()],
+                node_type: Some(GsnNodeType::Strategy),
+                multiplicity: BTreeMap::from([("G3".to_owned(), "n".to_owned())]),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "G2".to_owned(),
+            GsnNode {
+                undeveloped: true,
+                node_type: Some(GsnNodeType::Goal),
+                ..Default::default()
+            },
+        );
+        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true, &[]).is_err());
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(d.messages[0].module, Some("".to_owned()));
+        assert_eq!(d.messages[0].diag_type, DiagType::Error);
+        assert_eq!(
+            d.messages[0].msg,
+            "V14: Element S1 declares multiplicity for G3, but S1 has no relation to G3."
+        );
+        assert_eq!(d.errors, 1);
+        assert_eq!(d.warnings, 0);
+    }
+
+    #[test]
+    fn pattern_single_choice() {
+        let mut d = Diagnostics::default();
+        let mut nodes = BTreeMap::<String, GsnNode>::new();
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                supported_by: vec!["G2".to_owned()],
+                node_type: Some(GsnNodeType::Goal),
+                choice: BTreeMap::from([("1 of 1".to_owned(), vec!["G2".to_owned()])]),
+                ..Default::default()
+            },
+        );
+        nodes.insert(
+            "G2".to_owned(),
+            GsnNode {
+                undeveloped: true,
+                node_type: Some(GsnNodeType::Goal),
+                ..Default::default()
+            },
+        );
+        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true, &[]).is_err());
+        assert_eq!(d.messages.len(), 1);
+        assert_eq!(d.messages[0].module, Some("".to_owned()));
+        assert_eq!(d.messages[0].diag_type, DiagType::Error);
+        assert_eq!(
+            d.messages[0].msg,
+            "V15: Choice 1 of 1 of element G1 does not offer at least two alternatives."
+        );
+        assert_eq!(d.errors, 1);
+        assert_eq!(d.warnings, 0);
+    }
+
+    #[test]
+    fn pattern_conflicting() {
+        let mut d = Diagnostics::default();
+        let mut nodes = BTreeMap::<String, GsnNode>::new();
+        nodes.insert(
+            "G1".to_owned(),
+            GsnNode {
+                supported_by: vec!["G2".to_owned(), "G3".to_owned()],
+                node_type: Some(GsnNodeType::Goal),
+                multiplicity: BTreeMap::from([("G2".to_owned(), "n".to_owned())]),
+                optional: vec!["G2".to_owned()],
+                choice: BTreeMap::from([
+                    ("a".to_owned(), vec!["G2".to_owned(), "G3".to_owned()]),
+                    ("b".to_owned(), vec!["G2".to_owned(), "G3".to_owned()]),
+                ]),
+                ..Default::default()
+            },
+        );
+        for id in ["G2", "G3"] {
+            nodes.insert(
+                id.to_owned(),
+                GsnNode {
+                    undeveloped: true,
+                    node_type: Some(GsnNodeType::Goal),
+                    ..Default::default()
+                },
+            );
+        }
+        assert!(validate_module(&mut d, "", &Module::default(), &nodes, true, true, &[]).is_err());
+        assert_eq!(d.messages.len(), 3);
+        assert_eq!(
+            d.messages[0].msg,
+            "V16: Relation from G1 to G2 is marked as optional and has a multiplicity."
+        );
+        assert_eq!(
+            d.messages[1].msg,
+            "V19: Relation from G1 to G2 is part of more than one choice."
+        );
+        assert_eq!(
+            d.messages[2].msg,
+            "V19: Relation from G1 to G3 is part of more than one choice."
+        );
+        assert_eq!(d.errors, 3);
+        assert_eq!(d.warnings, 0);
+    }
 }
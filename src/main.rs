@@ -1,16 +1,17 @@
 use anyhow::{Context, Result, anyhow};
 use clap::parser::ValueSource;
 use clap::{Arg, ArgAction, Command, value_parser};
-use file_utils::{create_file_incl_parent, translate_to_output_path};
+use file_utils::{create_file_incl_parent, is_json_file, translate_to_output_path};
 use render::RenderOptions;
 use serde_saphyr::{DuplicateKeyPolicy, RequireIndent};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::error::Error;
 use std::fmt::Display;
 use std::io::{BufReader, stdout};
 use std::path::{Path, PathBuf};
 use std::{collections::btree_map::Entry, fs::File};
 
+mod config;
 mod diagnostics;
 mod dirgraph;
 mod dirgraphsvg;
@@ -19,9 +20,15 @@ mod gsn;
 mod outputs;
 mod render;
 
-use diagnostics::Diagnostics;
+use config::{Config, ProjectFile, parse_rename, parse_severity, parse_sibling_order};
+use diagnostics::{BaselineEntry, Diagnostics, STRUCTURAL_CHECKS, SourceLocation};
 use dirgraphsvg::escape_text;
-use gsn::{FindModuleByPath, GsnDocument, GsnNode, Module, ModuleInformation, Origin};
+use gsn::instantiation::{Binding, instantiate, resolve_relative_to};
+use gsn::location::{ElementLocation, read_locations};
+use gsn::{
+    FindModuleByPath, GsnDocument, GsnNode, Module, ModuleInformation, Origin, SiblingOrder,
+    get_source_name,
+};
 
 const MODULE_INFORMATION_NODE: &str = "module";
 
@@ -32,36 +39,37 @@ const MODULE_INFORMATION_NODE: &str = "module";
 fn main() -> Result<()> {
     let mut command = build_command_options();
     let matches = command.clone().get_matches();
+    if matches.value_source("SCHEMA") == Some(ValueSource::CommandLine) {
+        let mut output = match matches.get_one::<String>("SCHEMA") {
+            Some(path) => create_file_incl_parent(Path::new(path))?,
+            None => Box::new(stdout().lock()) as Box<dyn std::io::Write>,
+        };
+        return outputs::render_schema(&mut output);
+    }
+    let config = Config::new(&matches, ProjectFile::from_matches(&matches)?)?;
 
-    let mut diags = Diagnostics::default();
+    let mut diags = new_diagnostics(&config)?;
 
-    let inputs: Vec<String> = matches
-        .get_many::<String>("INPUT")
-        .into_iter()
-        .flatten()
-        .cloned()
-        .collect();
-    let layers = matches
-        .get_many::<String>("LAYERS")
-        .into_iter()
-        .flatten()
-        .map(AsRef::as_ref)
-        .collect::<Vec<_>>();
-    let excluded_modules = matches
-        .get_many::<String>("EXCLUDED_MODULE")
-        .into_iter()
-        .flatten()
+    let layers = config.layers.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+    let excluded_modules = config
+        .excluded_modules
+        .iter()
         .map(AsRef::as_ref)
         .collect::<Vec<_>>();
-    if matches.value_source("INPUT") == Some(ValueSource::DefaultValue)
-        && !Path::new(inputs.first().unwrap()).exists()
+    if config.default_inputs && !Path::new(config.inputs.first().unwrap()).exists()
     // unwrap ok, since default provided
     {
         command.print_help()?;
         Err(anyhow!("index.gsn.yaml not found."))
+    } else if config.format {
+        format_inputs(&config)
+    } else if let Some((old, new)) = &config.rename {
+        rename_inputs(&config, old, new)
     } else {
-        // unwrap ok, since default value provided.
-        let output_directory = matches.get_one::<String>("OUTPUT_DIRECTORY").unwrap();
+        if config.fix {
+            fix_inputs(&config, &excluded_modules, &layers)?;
+        }
+        let output_directory = &config.output_directory;
 
         let mut nodes = BTreeMap::<String, GsnNode>::new();
 
@@ -70,13 +78,22 @@ fn main() -> Result<()> {
 
         // Closure is important here, otherwise main is left with ? operator
         let read_and_check = || -> Result<()> {
-            read_inputs(
-                &inputs,
+            let read = read_inputs(
+                &config.inputs,
                 &mut nodes,
                 &mut modules,
                 &mut diags,
                 output_directory,
-            )?;
+                config.sibling_order,
+                &config.include_directories,
+            );
+            // Everything that could be read is validated to report as many findings as possible.
+            if read
+                .as_ref()
+                .is_err_and(|e| !e.is::<ValidationOrCheckError>() || nodes.is_empty())
+            {
+                return read;
+            }
             // Validate
             validate_and_check(
                 &mut nodes,
@@ -84,29 +101,24 @@ fn main() -> Result<()> {
                 &mut diags,
                 &excluded_modules,
                 &layers,
-                matches.get_flag("EXTENDED_CHECK"),
-                matches.get_flag("WARN_DIALECTIC"),
+                config.extended_check,
+                config.warn_dialectic,
             )
+            .and(read)
         }();
         // Ignore error, if errors are found, this is handled in output_messages
         match read_and_check {
             Err(e) if e.is::<ValidationOrCheckError>() => Ok(()),
             Err(e) => Err(e),
             Ok(_) => {
-                if !matches.get_flag("CHECK_ONLY") {
+                if !config.check_only && config.instantiate.is_none() && config.renumber.is_none() {
                     // Create output directory
                     if !std::path::Path::new(&output_directory).exists() {
                         std::fs::create_dir_all(output_directory).with_context(|| {
                             format!("Could not create output directory {output_directory}")
                         })?;
                     }
-                    let embed_stylesheets = matches.get_flag("EMBED_CSS");
-                    let mut stylesheets = matches
-                        .get_many::<String>("STYLESHEETS")
-                        .into_iter()
-                        .flatten()
-                        .cloned()
-                        .collect::<Vec<_>>();
+                    let mut stylesheets = config.stylesheets.to_owned();
                     // Append stylesheets from modules
                     stylesheets.append(
                         &mut modules
@@ -117,42 +129,222 @@ fn main() -> Result<()> {
                     // Copy stylesheets if necessary and prepare paths
                     copy_and_prepare_stylesheets(
                         &mut stylesheets,
-                        embed_stylesheets,
+                        config.embed_stylesheets,
                         output_directory,
                     )?;
-                    let mut render_options = RenderOptions::new(
-                        &matches,
-                        stylesheets,
-                        embed_stylesheets,
-                        output_directory,
-                    );
+                    let mut render_options = RenderOptions::new(&config, stylesheets);
                     // Add missing nodes that may not exist because references checks have been excluded
                     add_missing_nodes_and_modules(&mut nodes, &mut modules, &mut render_options);
                     // Output views
                     print_outputs(&nodes, &modules, &render_options)?;
                 }
-                if let Some(ValueSource::CommandLine) = matches.value_source("STATISTICS") {
-                    let mut output = match matches.get_one::<String>("STATISTICS") {
+                if let Some(statistics) = &config.statistics {
+                    let mut output = match statistics {
                         Some(path) => Box::new(File::create(path)?) as Box<dyn std::io::Write>,
                         None => Box::new(stdout().lock()) as Box<dyn std::io::Write>,
                     };
                     outputs::render_statistics(&mut output, &nodes, &modules)?;
                 }
 
-                if let Some(ValueSource::CommandLine) = matches.value_source("YAMLDUMP") {
-                    let mut output = match matches.get_one::<String>("YAMLDUMP") {
+                if let Some(yaml_dump) = &config.yaml_dump {
+                    let mut output = match yaml_dump {
                         Some(path) => create_file_incl_parent(Path::new(path))?,
                         None => Box::new(stdout().lock()) as Box<dyn std::io::Write>,
                     };
                     outputs::render_yaml_docs(&mut output, &nodes, &modules)?;
                 }
+
+                if let Some(binding_path) = &config.instantiate {
+                    instantiate_pattern(binding_path, &nodes, &modules, &mut diags)?;
+                }
+
+                if let Some(module) = &config.renumber {
+                    renumber_module(module, &nodes, &modules, output_directory)?;
+                }
                 Ok(())
             }
         }?;
 
+        if let Some(baseline_path) = &config.write_baseline {
+            let mut output = create_file_incl_parent(Path::new(baseline_path))?;
+            outputs::render_baseline(&mut output, &diags)?;
+        }
+
         // Output diagnostic messages
-        output_messages(&diags)
+        output_messages(
+            &diags,
+            &config.diagnostics_format,
+            config.diagnostics_output.as_ref(),
+        )
+    }
+}
+
+///
+/// Create diagnostics with the severity policy and the baseline of `config`.
+///
+fn new_diagnostics(config: &Config) -> Result<Diagnostics> {
+    let mut diags = Diagnostics {
+        deny_warnings: config.deny_warnings,
+        ..Default::default()
+    };
+    for (check, severity) in &config.severities {
+        diags.set_severity(check, *severity);
+    }
+    if let Some(baseline_path) = &config.baseline {
+        diags.set_baseline(&read_baseline(baseline_path)?);
+    }
+    Ok(diags)
+}
+
+///
+/// Read the inputs to modify them in place.
+/// If the inputs cannot be read completely, the messages are output and processing stops.
+///
+fn read_inputs_for_editing(
+    config: &Config,
+    nodes: &mut BTreeMap<String, GsnNode>,
+    modules: &mut BTreeMap<String, Module>,
+    diags: &mut Diagnostics,
+) -> Result<()> {
+    match read_inputs(
+        &config.inputs,
+        nodes,
+        modules,
+        diags,
+        &config.output_directory,
+        config.sibling_order,
+        &config.include_directories,
+    ) {
+        Err(e) if e.is::<ValidationOrCheckError>() => {
+            output_messages(
+                diags,
+                &config.diagnostics_format,
+                config.diagnostics_output.as_ref(),
+            )?;
+            Err(anyhow!("Input files are not modified due to errors."))
+        }
+        result => result,
+    }
+}
+
+///
+/// Fix findings in the input files that can be repaired mechanically.
+/// The applied edits are printed. The inputs are checked again afterwards as usual.
+///
+fn fix_inputs(config: &Config, excluded_modules: &[&str], layers: &[&str]) -> Result<()> {
+    let mut diags = new_diagnostics(config)?;
+    let mut nodes = BTreeMap::<String, GsnNode>::new();
+    let mut modules = BTreeMap::<String, Module>::new();
+    read_inputs_for_editing(config, &mut nodes, &mut modules, &mut diags)?;
+    // Errors stop processing, but the findings up to then can be fixed nevertheless.
+    match validate_and_check(
+        &mut nodes,
+        &modules,
+        &mut diags,
+        excluded_modules,
+        layers,
+        config.extended_check,
+        config.warn_dialectic,
+    ) {
+        Err(e) if !e.is::<ValidationOrCheckError>() => return Err(e),
+        _ => (),
+    }
+    let fixes = gsn::fix::fix_findings(&diags)?;
+    for fix in &fixes {
+        eprintln!("{}: Fixed {}: {}", fix.file, fix.element, fix.description);
+    }
+    eprintln!("{} findings fixed.", fixes.len());
+    Ok(())
+}
+
+///
+/// Format the input files and all files they use in the canonical layout.
+/// With `--check`, the files are not modified, but an error is returned if any file is not formatted.
+///
+fn format_inputs(config: &Config) -> Result<()> {
+    let mut diags = Diagnostics::default();
+    let mut nodes = BTreeMap::<String, GsnNode>::new();
+    let mut modules = BTreeMap::<String, Module>::new();
+    read_inputs_for_editing(config, &mut nodes, &mut modules, &mut diags)?;
+    // JSON files are typically generated, thus they are not formatted.
+    let files = modules
+        .values()
+        .map(|m| m.orig_file_name.as_str())
+        .filter(|f| !is_json_file(f))
+        .collect::<BTreeSet<_>>();
+    let mut unformatted = 0;
+    for file in files {
+        let content =
+            std::fs::read_to_string(file).with_context(|| format!("Failed to open file {file}"))?;
+        let formatted = gsn::format::format_source(&content);
+        if formatted != content {
+            unformatted += 1;
+            if config.check_only {
+                eprintln!("{file}: Not formatted.");
+            } else {
+                std::fs::write(file, formatted)
+                    .with_context(|| format!("Failed to write file {file}."))?;
+                eprintln!("{file}: Formatted.");
+            }
+        }
+    }
+    if config.check_only && unformatted > 0 {
+        Err(anyhow!("{unformatted} files are not formatted."))
+    } else {
+        Ok(())
+    }
+}
+
+///
+/// Rename element `old` to `new` in the input files and all files they use.
+///
+fn rename_inputs(config: &Config, old: &str, new: &str) -> Result<()> {
+    let mut diags = Diagnostics::default();
+    let mut nodes = BTreeMap::<String, GsnNode>::new();
+    let mut modules = BTreeMap::<String, Module>::new();
+    read_inputs_for_editing(config, &mut nodes, &mut modules, &mut diags)?;
+    let renamings = gsn::rename::rename_element(&nodes, &modules, old, new)?;
+    for renaming in &renamings {
+        eprintln!(
+            "{}: Renamed {old} to {new} ({} occurrences).",
+            renaming.file, renaming.occurrences
+        );
     }
+    Ok(())
+}
+
+///
+/// Renumber the elements of `module` in all input files.
+/// The mapping from old to new IDs is written to `renumbering.yaml` in the output directory.
+///
+fn renumber_module(
+    module: &str,
+    nodes: &BTreeMap<String, GsnNode>,
+    modules: &BTreeMap<String, Module>,
+    output_directory: &str,
+) -> Result<()> {
+    let mapping = gsn::rename::get_renumbering(nodes, modules, module)?;
+    let renames = mapping
+        .iter()
+        .map(|(old, new)| (old.as_str(), new.as_str()))
+        .collect();
+    for renaming in gsn::rename::rename_elements(modules, &renames)? {
+        eprintln!(
+            "{}: Renumbered elements of {module} ({} occurrences).",
+            renaming.file, renaming.occurrences
+        );
+    }
+    let path = Path::new(output_directory).join("renumbering.yaml");
+    let mut output = create_file_incl_parent(&path)?;
+    for (old, new) in &mapping {
+        writeln!(output, "{old}: {new}")?;
+    }
+    eprintln!(
+        "{} elements renumbered. Mapping written to {}.",
+        mapping.len(),
+        path.display()
+    );
+    Ok(())
 }
 
 #[derive(PartialEq, Debug)]
@@ -166,18 +358,76 @@ impl Display for ValidationOrCheckError {
 
 impl Error for ValidationOrCheckError {}
 
+///
+/// Instantiate a pattern according to the binding file at `binding_path`.
+/// The instantiated module is written to the output file named in the binding or standard output.
+///
+fn instantiate_pattern(
+    binding_path: &str,
+    nodes: &BTreeMap<String, GsnNode>,
+    modules: &BTreeMap<String, Module>,
+    diags: &mut Diagnostics,
+) -> Result<()> {
+    let reader = BufReader::new(
+        File::open(binding_path).context(format!("Failed to open file {binding_path}"))?,
+    );
+    let binding: Binding = serde_saphyr::from_reader(reader)
+        .context(format!("Failed to parse YAML from file {binding_path}"))?;
+    // Errors are reported in diagnostics
+    if let Ok((instance_nodes, instance_modules)) =
+        instantiate(diags, &binding, binding_path, nodes, modules)
+    {
+        let mut output = match &binding.output {
+            Some(path) => {
+                create_file_incl_parent(Path::new(&resolve_relative_to(binding_path, path)))?
+            }
+            None => Box::new(stdout().lock()) as Box<dyn std::io::Write>,
+        };
+        // Other nodes are needed to rank the instantiated nodes correctly
+        let mut all_nodes = nodes.to_owned();
+        all_nodes.extend(instance_nodes);
+        outputs::render_yaml_docs(&mut output, &all_nodes, &instance_modules)?;
+    }
+    Ok(())
+}
+
 ///
 /// Create clap command line arguments
 ///
 ///
-fn build_command_options() -> Command {
+pub(crate) fn build_command_options() -> Command {
     clap::command!()
+        .arg(
+            Arg::new("CONFIG")
+                .help("Read options from <CONFIG> project file instead of gsn2x.yaml.")
+                .long("config")
+                .value_name("CONFIG")
+                .action(ArgAction::Set)
+                .require_equals(true),
+        )
+        .arg(
+            Arg::new("SCHEMA")
+                .help("Output JSON Schema of the input format to <SCHEMA> file or standard output.")
+                .long("schema")
+                .action(ArgAction::Set)
+                .require_equals(true)
+                .num_args(0..=1),
+        )
         .arg(
             Arg::new("INPUT")
                 .help("Sets the input file(s) to use.")
                 .action(ArgAction::Append)
                 .default_values(["index.gsn.yaml"]),
         )
+        .arg(
+            Arg::new("INCLUDE_DIRECTORIES")
+                .help("Search used files also in <DIR>. Can be used multiple times.")
+                .short('I')
+                .long("include")
+                .value_name("DIR")
+                .action(ArgAction::Append)
+                .require_equals(true),
+        )
         .arg(
             Arg::new("CHECK_ONLY")
                 .help("Only check the input file(s), but do not output graphs.")
@@ -209,6 +459,98 @@ fn build_command_options() -> Command {
                 .action(ArgAction::SetTrue)
                 .help_heading("CHECKS"),
         )
+        .arg(
+            Arg::new("DIAGNOSTICS_FORMAT")
+                .help("Output messages as text, jsonl (JSON Lines) or sarif.")
+                .long("diagnostics")
+                .value_name("FORMAT")
+                .value_parser(["text", "jsonl", "sarif"])
+                .action(ArgAction::Set)
+                .require_equals(true)
+                .default_value("text")
+                .hide_default_value(true)
+                .hide_possible_values(true)
+                .help_heading("CHECKS"),
+        )
+        .arg(
+            Arg::new("DIAGNOSTICS_OUTPUT")
+                .help("Output jsonl or sarif messages to <DIAG_FILE> file.")
+                .long("diagnostics-output")
+                .value_name("DIAG_FILE")
+                .action(ArgAction::Set)
+                .require_equals(true)
+                .help_heading("CHECKS"),
+        )
+        .arg(
+            Arg::new("SEVERITY")
+                .help("Set severity of a check or category to error or warning.")
+                .long("severity")
+                .value_name("CHECK=LEVEL")
+                .value_parser(parse_severity)
+                .action(ArgAction::Append)
+                .require_equals(true)
+                .help_heading("CHECKS"),
+        )
+        .arg(
+            Arg::new("DENY_WARNINGS")
+                .help("Treat all warnings as errors.")
+                .long("deny-warnings")
+                .action(ArgAction::SetTrue)
+                .help_heading("CHECKS"),
+        )
+        .arg(
+            Arg::new("BASELINE")
+                .help("Only report findings that are not in <BASELINE> file.")
+                .long("baseline")
+                .value_name("BASELINE")
+                .action(ArgAction::Set)
+                .require_equals(true)
+                .help_heading("CHECKS"),
+        )
+        .arg(
+            Arg::new("WRITE_BASELINE")
+                .help("Write all findings to <BASELINE> file.")
+                .long("write-baseline")
+                .value_name("BASELINE")
+                .action(ArgAction::Set)
+                .require_equals(true)
+                .help_heading("CHECKS"),
+        )
+        .arg(
+            Arg::new("FIX")
+                .help("Fix findings of C11, V02 and V05 in the input file(s).")
+                .long("fix")
+                .action(ArgAction::SetTrue)
+                .help_heading("CHECKS"),
+        )
+        .arg(
+            Arg::new("FORMAT")
+                .help("Format the input file(s). Only report unformatted files with -c.")
+                .long("fmt")
+                .action(ArgAction::SetTrue)
+                .help_heading("CHECKS"),
+        )
+        .arg(
+            Arg::new("RENAME")
+                .help("Rename element OLD to NEW in the input file(s).")
+                .long("rename")
+                .value_name("OLD=NEW")
+                .value_parser(parse_rename)
+                .action(ArgAction::Set)
+                .require_equals(true)
+                .conflicts_with_all(["FIX", "FORMAT"])
+                .help_heading("CHECKS"),
+        )
+        .arg(
+            Arg::new("RENUMBER")
+                .help("Renumber the elements of <MODULE> in the input file(s).")
+                .long("renumber")
+                .value_name("MODULE")
+                .action(ArgAction::Set)
+                .require_equals(true)
+                .conflicts_with_all(["FIX", "FORMAT", "RENAME", "INSTANTIATE"])
+                .help_heading("CHECKS"),
+        )
         .arg(
             Arg::new("NO_ARGUMENT_VIEW")
                 .help("Do not output of argument view for provided input files.")
@@ -306,6 +648,16 @@ fn build_command_options() -> Command {
                 .num_args(0..=1)
                 .help_heading("OUTPUT"),
         )
+        .arg(
+            Arg::new("INSTANTIATE")
+                .help("Instantiate a pattern module with the parameters given in <BINDING> file.")
+                .long("instantiate")
+                .value_name("BINDING")
+                .action(ArgAction::Set)
+                .require_equals(true)
+                .conflicts_with("CHECK_ONLY")
+                .help_heading("OUTPUT"),
+        )
         .arg(
             Arg::new("LAYERS")
                 .help("Output additional layer. Can be used multiple times.")
@@ -376,6 +728,16 @@ fn build_command_options() -> Command {
                 .help_heading("OUTPUT MODIFICATION"),
             // Intentionally no default value, to allow formatting via YAML.
         )
+        .arg(
+            Arg::new("SIBLING_ORDER")
+                .help("Order elements on the same rank lexicographically, natural or as authored.")
+                .long("sibling-order")
+                .value_name("ORDER")
+                .value_parser(parse_sibling_order)
+                .action(ArgAction::Set)
+                .require_equals(true)
+                .help_heading("OUTPUT MODIFICATION"),
+        )
 }
 
 ///
@@ -412,10 +774,13 @@ fn add_missing_nodes_and_modules(
         "Unknown".to_owned(),
         Module {
             orig_file_name: "".to_owned(),
+            document: None,
             meta: ModuleInformation::new("Unknown".to_owned()),
             origin: Origin::Excluded,
             canonical_path: None,
             output_path: None,
+            location: None,
+            sibling_order: SiblingOrder::default(),
         },
     );
 }
@@ -430,76 +795,134 @@ fn read_inputs(
     modules: &mut BTreeMap<String, Module>,
     diags: &mut Diagnostics,
     output_directory: &str,
+    sibling_order: SiblingOrder,
+    include_directories: &[String],
 ) -> Result<()> {
     let mut copied_inputs: Vec<String> = inputs.iter().map(|i| i.replace('\\', "/")).collect();
     let mut first_run = true;
+    let mut input_errors = false;
     'outer: loop {
         let mut additional_inputs = vec![];
         for input in &copied_inputs {
-            let reader =
-                BufReader::new(File::open(input).context(format!("Failed to open file {input}"))?);
-
-            let options = serde_saphyr::options! {
-                duplicate_keys: DuplicateKeyPolicy::Error,
-                require_indent: RequireIndent::Uniform(None),
-            };
-
-            let mut n: BTreeMap<String, GsnDocument> =
-                serde_saphyr::from_reader_with_options(reader, options)
-                    .context(format!("Failed to parse YAML from file {input}"))?;
-            let meta: ModuleInformation = match n.remove_entry(MODULE_INFORMATION_NODE) {
-                Some((_, GsnDocument::ModuleInformation(x))) => x,
-                _ => {
-                    let module_name = escape_text(&input.to_owned());
-                    ModuleInformation::new(module_name)
-                }
+            let content =
+                std::fs::read_to_string(input).context(format!("Failed to open file {input}"))?;
+            // Other files are still read to report as many findings as possible.
+            let Ok(mut documents) = parse_documents(input, &content, diags) else {
+                input_errors = true;
+                continue;
             };
+            if documents.is_empty() {
+                documents.push(BTreeMap::new());
+            }
+            // Locations are only used for diagnostics, thus, missing ones are tolerated.
+            let mut document_locations = read_locations(input, &content).unwrap_or_default();
+            document_locations.resize_with(documents.len(), BTreeMap::new);
+            // Documents are only numbered if there are several in a file.
+            let is_stream = documents.len() > 1;
+            for (index, (mut n, mut locations)) in
+                documents.into_iter().zip(document_locations).enumerate()
+            {
+                let document = is_stream.then_some(index + 1);
+                let source = get_source_name(input, document);
+                let meta: ModuleInformation = match n.remove_entry(MODULE_INFORMATION_NODE) {
+                    Some((_, GsnDocument::ModuleInformation(x))) => x,
+                    _ => {
+                        let module_name = escape_text(&source);
+                        ModuleInformation::new(module_name)
+                    }
+                };
 
-            // Add filename and module name to module list
-            let module = meta.name.to_owned();
-            let pb = PathBuf::from(input)
-                .canonicalize()
-                .with_context(|| format!("Failed to open file {input}."))?;
-            let module_name_exists = modules.find_module_by_path(&pb).is_some();
-            // Check for duplicate module name
-            match modules.entry(module.to_owned()) {
-                Entry::Vacant(e) if !module_name_exists => {
-                    e.insert(Module {
-                        orig_file_name: input.to_owned().to_owned(),
-                        meta: meta.clone(),
-                        origin: if first_run {
-                            Origin::CommandLine
-                        } else {
-                            Origin::File(input.to_owned())
-                        },
-                        canonical_path: Some(pb),
-                        output_path: translate_to_output_path(output_directory, input, Some("svg"))
+                // Add filename and module name to module list
+                let module = meta.name.to_owned();
+                let pb = PathBuf::from(input)
+                    .canonicalize()
+                    .with_context(|| format!("Failed to open file {input}."))?;
+                let module_name_exists = modules
+                    .values()
+                    .any(|m| m.canonical_path.as_ref() == Some(&pb) && m.document == document);
+                let output_extension = match document {
+                    Some(document) => format!("{document}.svg"),
+                    None => "svg".to_owned(),
+                };
+                // Check for duplicate module name
+                match modules.entry(module.to_owned()) {
+                    Entry::Vacant(e) if !module_name_exists => {
+                        let module_info = e.insert(Module {
+                            orig_file_name: input.to_owned().to_owned(),
+                            document,
+                            meta: meta.clone(),
+                            origin: if first_run {
+                                Origin::CommandLine
+                            } else {
+                                Origin::File(source.to_owned())
+                            },
+                            canonical_path: Some(pb),
+                            output_path: translate_to_output_path(
+                                output_directory,
+                                input,
+                                Some(&output_extension),
+                            )
                             .ok(),
-                    });
-                    check_and_add_nodes(n, nodes, &module, diags, input, meta.char_wrap);
-                    // Remember additional files to read
-                    let imported_files = get_uses_files(&meta, input, diags);
-                    additional_inputs.extend(imported_files.to_vec());
-                }
-                Entry::Vacant(_) => {
-                    unreachable!()
-                }
-                Entry::Occupied(e) => {
-                    diags.add_error(
-                        Some(&module),
-                        format!(
-                            "C06: Module in {} was already present in {} provided by {}.",
-                            input,
-                            e.get().orig_file_name,
-                            e.get().origin,
-                        ),
-                    );
-                    // A circle may be detected, conservatively bail out completely.
-                    break 'outer Err(ValidationOrCheckError {}.into());
+                            location: Some(
+                                locations
+                                    .remove(MODULE_INFORMATION_NODE)
+                                    .map(|l| l.location)
+                                    .unwrap_or_else(|| SourceLocation {
+                                        file: input.to_owned(),
+                                        line: 1,
+                                        column: 1,
+                                    }),
+                            ),
+                            sibling_order: meta.sibling_order.unwrap_or(sibling_order),
+                        });
+                        // Suppressions of the module apply to the findings while reading, too.
+                        diags.suppress_for_module(&module, &module_info.meta.suppress);
+                        check_and_add_nodes(n, nodes, module_info, diags, locations);
+                        // Remember additional files to read
+                        if diags
+                            .check(|d| {
+                                get_uses_files(
+                                    module_info,
+                                    include_directories,
+                                    d,
+                                    &mut additional_inputs,
+                                )
+                            })
+                            .is_err()
+                        {
+                            input_errors = true;
+                        }
+                    }
+                    Entry::Vacant(_) => {
+                        unreachable!()
+                    }
+                    Entry::Occupied(e) => {
+                        let errors = diags.errors;
+                        diags.add_error_at(
+                            Some(&module),
+                            locations.get(MODULE_INFORMATION_NODE).map(|l| &l.location),
+                            &[],
+                            format!(
+                                "C06: Module in {} was already present in {} provided by {}.",
+                                source,
+                                e.get().source(),
+                                e.get().origin,
+                            ),
+                        );
+                        // A circle may be detected, conservatively bail out completely.
+                        // If the error is not counted, the module is skipped.
+                        if diags.errors > errors {
+                            break 'outer Err(ValidationOrCheckError {}.into());
+                        }
+                    }
                 }
             }
         }
+        // All used files are read before errors are reported.
         if additional_inputs.is_empty() {
+            if input_errors {
+                break Err(ValidationOrCheckError {}.into());
+            }
             break Ok(());
         } else {
             copied_inputs.clear();
@@ -509,36 +932,107 @@ fn read_inputs(
     }
 }
 
+///
+/// Parse the documents in `content` of file `input`.
+/// Syntax errors are reported with their position in the file.
+///
+fn parse_documents(
+    input: &str,
+    content: &str,
+    diags: &mut Diagnostics,
+) -> Result<Vec<BTreeMap<String, GsnDocument>>, ()> {
+    if is_json_file(input) {
+        // JSON is valid YAML and is parsed as such below to get identical diagnostics.
+        // Syntax errors are reported in terms of JSON, though.
+        if let Err(e) = serde_json::from_str::<serde_json::Value>(content) {
+            let location = SourceLocation {
+                file: input.to_owned(),
+                line: e.line() as u64,
+                column: e.column() as u64,
+            };
+            diags.add_error_at(
+                None,
+                Some(&location),
+                &[],
+                format!("Failed to parse JSON from file {input}: {e}"),
+            );
+            return Err(());
+        }
+    }
+
+    let options = serde_saphyr::options! {
+        duplicate_keys: DuplicateKeyPolicy::Error,
+        require_indent: RequireIndent::Uniform(None),
+    };
+
+    serde_saphyr::from_multiple_with_options(content, options).map_err(|e| {
+        let location = e.location().map(|l| SourceLocation {
+            file: input.to_owned(),
+            line: l.line(),
+            column: l.column(),
+        });
+        diags.add_error_at(
+            None,
+            location.as_ref(),
+            &[],
+            format!(
+                "Failed to parse YAML from file {input}: {}",
+                e.without_snippet()
+            ),
+        );
+    })
+}
+
 ///
 /// Get files that are marked as "uses" by current module.
 ///
+/// Relative paths are resolved relative to the file of the module first,
+/// then relative to the `include_directories` in the given order.
+/// The found files are added to `used_files`.
+/// If a file cannot be found, an error is reported with all paths that were tried.
 ///
 fn get_uses_files(
-    meta: &ModuleInformation,
-    input: &String,
+    module: &Module,
+    include_directories: &[String],
     diags: &mut Diagnostics,
-) -> Vec<String> {
-    let imported_files: Vec<String> = meta
-        .uses
-        .iter()
-        .filter_map(|r| match PathBuf::from(r) {
-            x if x.is_relative() => PathBuf::from(input).parent().map(|p| {
-                let mut new_r = p.to_path_buf();
-                new_r.push(r);
-                new_r.to_string_lossy().to_string()
-            }),
-            x if x.is_absolute() => Some(r.to_owned()),
+    used_files: &mut Vec<String>,
+) -> Result<(), ()> {
+    let meta = &module.meta;
+    let mut missing = 0;
+    for r in &meta.uses {
+        let candidates: Vec<String> = match PathBuf::from(r) {
+            x if x.is_relative() => PathBuf::from(&module.orig_file_name)
+                .parent()
+                .into_iter()
+                .chain(include_directories.iter().map(Path::new))
+                .map(|p| p.join(r).to_string_lossy().replace('\\', "/"))
+                .collect(),
+            x if x.is_absolute() => vec![r.replace('\\', "/")],
             _ => {
                 diags.add_warning(
                     Some(&meta.name),
                     format!("Could not identify used file {r} in module; ignoring it."),
                 );
-                None
+                continue;
             }
-        })
-        .map(|i| i.replace('\\', "/"))
-        .collect();
-    imported_files
+        };
+        match candidates.iter().find(|c| Path::new(c).exists()) {
+            Some(file) => used_files.push(file.to_owned()),
+            None => {
+                diags.add_error_at(
+                    Some(&meta.name),
+                    module.location.as_ref(),
+                    &[],
+                    format!(
+                        "C21: Used file {r} is not found. Tried {}.",
+                        candidates.join(", ")
+                    ),
+                );
+                missing += 1;
+            }
+        }
+    }
+    if missing == 0 { Ok(()) } else { Err(()) }
 }
 
 ///
@@ -548,11 +1042,11 @@ fn get_uses_files(
 fn check_and_add_nodes(
     mut n: BTreeMap<String, GsnDocument>,
     nodes: &mut BTreeMap<String, GsnNode>,
-    module: &String,
+    module: &Module,
     diags: &mut Diagnostics,
-    input: &String,
-    char_wrap: Option<u32>,
+    mut locations: BTreeMap<String, ElementLocation>,
 ) {
+    let meta = &module.meta;
     // Check for duplicates, since they might be in separate files.
     let node_names: Vec<String> = n.keys().cloned().collect();
     for node_name in node_names {
@@ -561,30 +1055,37 @@ fn check_and_add_nodes(
                 Entry::Vacant(e) => match v {
                     GsnDocument::GsnNode(mut x) => {
                         // Remember module for node
-                        module.clone_into(&mut x.module);
+                        meta.name.clone_into(&mut x.module);
                         x.fix_node_type(&k);
-                        // Sort all edges lexicographically
-                        x.supported_by.sort();
-                        x.in_context_of.sort();
+                        // Sort all edges
+                        module.sibling_order.sort(&mut x.supported_by);
+                        module.sibling_order.sort(&mut x.in_context_of);
                         // Inherit char_wrap
                         if x.char_wrap.is_none() {
-                            x.char_wrap = char_wrap;
+                            x.char_wrap = meta.char_wrap;
+                        }
+                        // Elements in the module interface are public
+                        if meta.interface.contains(&k) {
+                            x.public = true;
                         }
+                        // Remember where element and its references are defined
+                        x.location = locations.remove(&k).map(Box::new);
                         e.insert(x);
                     }
                     _ => unreachable!(), // There can be only one MetaNode
                 },
                 Entry::Occupied(e) => {
-                    diags.add_error(
-                        Some(module),
+                    diags.add_error_at(
+                        Some(&meta.name),
+                        locations.get(&k).map(|l| &l.location),
+                        &[k.as_str()],
                         format!(
                             "C07: Element {} in {} was already present in {}.",
                             k,
-                            input,
+                            module.source(),
                             e.get().module,
                         ),
                     );
-                    break;
                 }
             }
         }
@@ -622,33 +1123,129 @@ fn validate_and_check(
         }
     } {
         for empty_module in empty_modules {
-            diags.add_error(
+            diags.add_error_at(
                 Some(&empty_module),
+                modules.get(&empty_module).and_then(|m| m.location.as_ref()),
+                &[],
                 "The module does not contain elements.".to_owned(),
             );
         }
         Err(ValidationOrCheckError {}.into())
     } else {
+        for (id, node) in nodes.iter() {
+            diags.suppress_for_element(id, &node.suppress);
+            // Suppressions of a module apply to all its elements, even if reported without module.
+            if let Some(module) = modules.get(&node.module) {
+                diags.suppress_for_element(id, &module.meta.suppress);
+            }
+        }
+        // Only errors that are counted stop processing.
         let result = || -> Result<(), ()> {
+            gsn::add_module_references(nodes, modules);
             for module_info in modules.values() {
                 // Validation for well-formedness is done unconditionally.
-                gsn::validation::validate_module(
-                    diags,
-                    &module_info.meta.name,
-                    module_info,
-                    nodes,
-                    extended_check,
-                    warn_dialectic,
-                )?;
+                diags.check(|d| {
+                    gsn::validation::validate_module(
+                        d,
+                        &module_info.meta.name,
+                        module_info,
+                        nodes,
+                        extended_check,
+                        warn_dialectic,
+                        layers,
+                    )
+                })?;
             }
-            gsn::extend_modules(diags, nodes, modules)?;
-            gsn::check::check_nodes(diags, nodes, excluded_modules)?;
-            gsn::check::check_layers(diags, nodes, layers)
+            // Interfaces are checked on the references as written,
+            // i.e., before extensions and contracts add references across modules.
+            let interfaces = diags.check(|d| {
+                gsn::check::check_module_interfaces(d, nodes, modules, excluded_modules)
+            });
+            diags.check(|d| gsn::extend_modules(d, nodes, modules))?;
+            diags.check(|d| gsn::apply_contracts(d, nodes, modules))?;
+            let unloaded_elements = find_unloaded_elements(nodes, modules);
+            diags.check(|d| {
+                gsn::check::check_nodes(d, nodes, excluded_modules, modules, &unloaded_elements)
+            })?;
+            interfaces?;
+            diags.check(|d| gsn::check::check_layers(d, nodes, layers))
         }();
         result.map_err(|_| ValidationOrCheckError {}.into())
     }
 }
 
+///
+/// Find elements in files next to the loaded modules that are not loaded themselves.
+///
+/// This is only done if there are references to elements that do not exist.
+/// Returns a map of element IDs to the files they are defined in.
+///
+fn find_unloaded_elements(
+    nodes: &BTreeMap<String, GsnNode>,
+    modules: &BTreeMap<String, Module>,
+) -> BTreeMap<String, String> {
+    let mut unloaded_elements = BTreeMap::new();
+    let has_unresolved_references = nodes.values().any(|n| {
+        n.supported_by
+            .iter()
+            .chain(n.in_context_of.iter())
+            .any(|r| !nodes.contains_key(r))
+            || match &n.challenges {
+                Some(gsn::Challenge::Node(r)) => !nodes.contains_key(r),
+                Some(gsn::Challenge::Relation((l, r))) => {
+                    !nodes.contains_key(l) || !nodes.contains_key(r)
+                }
+                None => false,
+            }
+    });
+    if !has_unresolved_references {
+        return unloaded_elements;
+    }
+    let directories = modules
+        .values()
+        .filter_map(|m| {
+            Path::new(&m.orig_file_name)
+                .parent()
+                .map(|p| p.to_path_buf())
+        })
+        .collect::<BTreeSet<_>>();
+    for entry in directories
+        .iter()
+        .filter_map(|d| std::fs::read_dir(Path::new(".").join(d)).ok())
+        .flatten()
+        .flatten()
+    {
+        let path = entry.path();
+        let file_name = path
+            .to_string_lossy()
+            .trim_start_matches("./")
+            .replace('\\', "/");
+        if path.extension().is_none_or(|e| e != "yaml" && e != "json")
+            || path
+                .canonicalize()
+                .is_ok_and(|p| modules.find_module_by_path(&p).is_some())
+        {
+            continue;
+        }
+        // Files that cannot be parsed are not of interest here.
+        if let Ok(documents) = std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|c| Ok(serde_saphyr::from_multiple::<BTreeMap<String, GsnDocument>>(&c)?))
+        {
+            for id in documents
+                .into_iter()
+                .flat_map(BTreeMap::into_keys)
+                .filter(|id| id != MODULE_INFORMATION_NODE)
+            {
+                unloaded_elements
+                    .entry(id)
+                    .or_insert_with(|| file_name.to_owned());
+            }
+        }
+    }
+    unloaded_elements
+}
+
 ///
 /// Print outputs
 ///
@@ -674,6 +1271,21 @@ fn print_outputs(
                 nodes,
                 render_options,
             )?;
+            // Each tree of a forest is additionally rendered on its own.
+            if module.meta.roots.len() > 1 {
+                for root in module.meta.roots.iter().filter(|r| nodes.contains_key(*r)) {
+                    let output_path = output_path.with_extension(format!("{root}.svg"));
+                    let mut output_file = create_file_incl_parent(&output_path)?;
+                    print!("Rendering \"{}\": ", output_path.display());
+                    render::render_argument(
+                        &mut output_file,
+                        &module.meta.name,
+                        modules,
+                        &gsn::get_root_tree(nodes, root),
+                        render_options,
+                    )?;
+                }
+            }
         }
     }
     // Output directory is already created. No need to add that.
@@ -713,27 +1325,69 @@ fn print_outputs(
 }
 
 ///
-/// Render to dot-file if not only validation is active.
+/// Output warnings and errors in the requested format.
 /// Output summary of warnings and errors.
 ///
-fn output_messages(diags: &Diagnostics) -> Result<()> {
-    for msg in &diags.messages {
-        eprintln!("{msg}");
+fn output_messages(
+    diags: &Diagnostics,
+    format: &str,
+    diagnostics_output: Option<&String>,
+) -> Result<()> {
+    if format == "text" {
+        for msg in &diags.messages {
+            eprintln!("{msg}");
+        }
+    } else {
+        let mut output = match diagnostics_output {
+            Some(path) => create_file_incl_parent(Path::new(path))?,
+            None => Box::new(stdout().lock()) as Box<dyn std::io::Write>,
+        };
+        if format == "sarif" {
+            outputs::render_diagnostics_sarif(&mut output, diags)?;
+        } else {
+            outputs::render_diagnostics_json_lines(&mut output, diags)?;
+        }
     }
-    if diags.errors == 0 {
+    let mut hidden = String::new();
+    if diags.suppressed > 0 {
+        hidden.push_str(&format!(" {} findings suppressed.", diags.suppressed));
+    }
+    if !diags.baselined.is_empty() {
+        hidden.push_str(&format!(" {} findings in baseline.", diags.baselined.len()));
+    }
+    if diags.errors == 0 && diags.hidden_structural_findings > 0 {
+        Err(anyhow!(
+            "Processing stopped, since {} findings of structural checks ({}) are not reported as errors, but leave elements that cannot be processed further.{hidden}",
+            diags.hidden_structural_findings,
+            STRUCTURAL_CHECKS.join(", ")
+        ))
+    } else if diags.errors == 0 {
         if diags.warnings > 0 {
-            eprintln!("Warning: {} warnings detected.", diags.warnings);
+            eprintln!("Warning: {} warnings detected.{hidden}", diags.warnings);
+        } else if !hidden.is_empty() {
+            eprintln!("{}", hidden.trim_start());
         }
         Ok(())
     } else {
         Err(anyhow!(
-            "{} errors and {} warnings detected.",
+            "{} errors and {} warnings detected.{hidden}",
             diags.errors,
             diags.warnings
         ))
     }
 }
 
+///
+/// Read the findings of a baseline file.
+///
+fn read_baseline(path: &str) -> Result<Vec<BaselineEntry>> {
+    let reader = BufReader::new(
+        File::open(path).with_context(|| format!("Failed to open baseline file {path}."))?,
+    );
+    serde_json::from_reader(reader)
+        .with_context(|| format!("Failed to parse baseline file {path}."))
+}
+
 ///
 /// Copy the stylesheets if they need to be in the output directory
 /// If they actually reference a URL, make the stylesheet reference a url.
@@ -789,7 +1443,7 @@ mod test {
             errors: 3,
             ..Default::default()
         };
-        let res = crate::output_messages(&d);
+        let res = crate::output_messages(&d, "text", None);
         assert!(res.is_err());
         assert_eq!(
             res.err().unwrap().to_string(),
@@ -797,6 +1451,21 @@ mod test {
         );
     }
 
+    #[test]
+    fn check_output_messages_suppressed() {
+        let d = Diagnostics {
+            warnings: 2,
+            errors: 3,
+            suppressed: 4,
+            ..Default::default()
+        };
+        let res = crate::output_messages(&d, "text", None);
+        assert_eq!(
+            res.err().unwrap().to_string(),
+            "3 errors and 2 warnings detected. 4 findings suppressed."
+        );
+    }
+
     #[test]
     fn check_output_messages_warnings() {
         let d = Diagnostics {
@@ -804,7 +1473,7 @@ mod test {
             errors: 0,
             ..Default::default()
         };
-        let res = crate::output_messages(&d);
+        let res = crate::output_messages(&d, "text", None);
         assert!(res.is_ok());
         assert_eq!(format!("{res:?}"), "Ok(())");
     }
@@ -816,7 +1485,7 @@ mod test {
             errors: 0,
             ..Default::default()
         };
-        let res = crate::output_messages(&d);
+        let res = crate::output_messages(&d, "text", None);
         assert!(res.is_ok());
         assert_eq!(format!("{res:?}"), "Ok(())");
     }
@@ -1,12 +1,17 @@
-use std::{collections::BTreeMap, io::Write};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+};
 
 use crate::{
+    diagnostics::{DiagType, Diagnostics},
     dirgraph::DirectedGraph,
     gsn::{self, GsnEdgeType, GsnNode, GsnNodeType, Module},
     render::RenderOptions,
 };
 
 use anyhow::Result;
+use serde_json::json;
 
 ///
 /// Output list of evidence.
@@ -90,7 +95,14 @@ pub(crate) fn render_statistics(
     writeln!(output, "Statistics")?;
     writeln!(output)?;
     writeln!(output, "Number of modules:   {}", modules.len())?;
-    writeln!(output, "Number of elements:  {}", nodes.len())?;
+    writeln!(
+        output,
+        "Number of elements:  {}",
+        nodes
+            .iter()
+            .filter(|n| n.1.node_type != Some(gsn::GsnNodeType::Module))
+            .count()
+    )?;
     writeln!(
         output,
         "  Goals:             {}",
@@ -180,12 +192,13 @@ pub(crate) fn render_yaml_docs(
 
     for (m_id, m) in modules {
         writeln!(&mut output, "--- # {} in {}\n", m_id, m.orig_file_name)?;
-        serde_saphyr::to_io_writer(&mut output, &m.meta)?;
+        serde_saphyr::to_io_writer(&mut output, &BTreeMap::from([("module", &m.meta)]))?;
         for rank in ranks.iter().flatten() {
             let rank_map = rank
                 .iter()
                 .map(|&n| (n, nodes.get(n).unwrap()))
-                .filter(|(_, n)| &n.module == m_id)
+                // Module references are created by gsn2x and not part of the input
+                .filter(|(_, n)| &n.module == m_id && n.node_type != Some(GsnNodeType::Module))
                 .collect::<BTreeMap<&str, &GsnNode>>();
             if !rank_map.is_empty() {
                 serde_saphyr::to_io_writer(&mut output, &rank_map)?;
@@ -194,6 +207,116 @@ pub(crate) fn render_yaml_docs(
         writeln!(&mut output, "... # {m_id}\n")?;
     }
 
-    // serde_yaml_ng::to_string(value)
+    Ok(())
+}
+
+///
+/// Output the JSON Schema of the input format.
+///
+pub(crate) fn render_schema(output: &mut impl Write) -> Result<()> {
+    serde_json::to_writer_pretty(&mut *output, &gsn::schema::json_schema())?;
+    writeln!(output)?;
+    Ok(())
+}
+
+///
+/// Output all findings as baseline.
+///
+pub(crate) fn render_baseline(output: &mut impl Write, diags: &Diagnostics) -> Result<()> {
+    serde_json::to_writer_pretty(&mut *output, &diags.get_baseline_entries())?;
+    writeln!(output)?;
+    Ok(())
+}
+
+///
+/// Output diagnostic messages as JSON Lines, i.e. one JSON object per line.
+///
+pub(crate) fn render_diagnostics_json_lines(
+    output: &mut impl Write,
+    diags: &Diagnostics,
+) -> Result<()> {
+    for msg in &diags.messages {
+        let record = json!({
+            "check": msg.get_check_id(),
+            "severity": msg.diag_type,
+            "module": msg.module,
+            "elements": msg.elements,
+            "location": msg.location,
+            "message": msg.get_text(),
+        });
+        writeln!(output, "{record}")?;
+    }
+    Ok(())
+}
+
+///
+/// Output diagnostic messages as SARIF 2.1.0 log.
+///
+/// Each check ID is a rule.
+/// Module and element IDs are provided as properties of each result.
+///
+pub(crate) fn render_diagnostics_sarif(output: &mut impl Write, diags: &Diagnostics) -> Result<()> {
+    let rules = diags
+        .messages
+        .iter()
+        .filter_map(|msg| msg.get_check_id())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|id| {
+            json!({
+                "id": id,
+                "helpUri": "https://jonasthewolf.github.io/gsn2x/checks.html",
+            })
+        })
+        .collect::<Vec<_>>();
+    let results = diags
+        .messages
+        .iter()
+        .map(|msg| {
+            let mut result = json!({
+                "level": match msg.diag_type {
+                    DiagType::Error => "error",
+                    DiagType::Warning => "warning",
+                },
+                "message": { "text": msg.get_text() },
+                "properties": {
+                    "module": msg.module,
+                    "elements": msg.elements,
+                },
+            });
+            if let Some(id) = msg.get_check_id() {
+                result["ruleId"] = json!(id);
+            }
+            if let Some(location) = &msg.location {
+                result["locations"] = json!([{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": location.file.replace('\\', "/") },
+                        "region": {
+                            "startLine": location.line,
+                            "startColumn": location.column,
+                        },
+                    },
+                }]);
+            }
+            result
+        })
+        .collect::<Vec<_>>();
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    });
+    serde_json::to_writer_pretty(&mut *output, &log)?;
+    writeln!(output)?;
     Ok(())
 }
@@ -1,14 +1,14 @@
+use crate::config::Config;
 use crate::dirgraph::EdgeDecorator;
 use crate::dirgraphsvg::edges::EdgeType;
 use crate::dirgraphsvg::{escape_node_id, nodes::SvgNode};
 use crate::file_utils::{get_filename, get_relative_path};
-use crate::gsn::{GsnNode, GsnNodeType, Module};
+use crate::gsn::{GsnEdgeType, GsnNode, GsnNodeType, Module, Multiplicity};
 use anyhow::Result;
-use clap::ArgMatches;
 use time::OffsetDateTime;
 use time::format_description::well_known::Iso8601;
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::io::Write;
 use std::time::SystemTime;
 
@@ -35,68 +35,41 @@ pub struct RenderOptions<'a> {
 }
 
 impl<'a> RenderOptions<'a> {
-    pub fn new(
-        matches: &'a ArgMatches,
-        stylesheets: Vec<String>,
-        embed_stylesheets: bool,
-        output_directory: &'a str,
-    ) -> Self {
-        let legend = get_render_legend(matches);
-        let layers = matches
-            .get_many::<String>("LAYERS")
-            .into_iter()
-            .flatten()
-            .cloned()
-            .collect::<Vec<_>>();
-        let masked_elements: Vec<String> = matches
-            .get_many::<String>("MASKED_MODULE")
-            .unwrap_or_default()
-            .chain(
-                matches
-                    .get_many::<String>("EXCLUDED_MODULE")
-                    .unwrap_or_default(),
-            )
+    pub fn new(config: &'a Config, stylesheets: Vec<String>) -> Self {
+        let legend = get_render_legend(config);
+        let masked_elements: Vec<String> = config
+            .masked_modules
+            .iter()
+            .chain(config.excluded_modules.iter())
             .cloned()
             .collect();
 
         RenderOptions {
             stylesheets,
             masked_elements,
-            layers,
+            layers: config.layers.to_owned(),
             legend,
-            embed_stylesheets,
-            architecture_filename: match matches.get_flag("NO_ARCHITECTURE_VIEW") {
-                true => None,
-                false => matches
-                    .get_one::<String>("ARCHITECTURE_VIEW")
-                    .and_then(|p| get_filename(p)),
-            },
-            evidence_filename: match matches.get_flag("NO_EVIDENCE") {
-                true => None,
-                false => matches
-                    .get_one::<String>("EVIDENCE")
-                    .and_then(|p| get_filename(p)),
-            },
-            complete_filename: match matches.get_flag("NO_COMPLETE_VIEW") {
-                true => None,
-                false => matches
-                    .get_one::<String>("COMPLETE_VIEW")
-                    .and_then(|p| get_filename(p)),
-            },
-            output_directory,
-            skip_argument: matches.get_flag("NO_ARGUMENT_VIEW"),
-            char_wrap: matches.get_one::<u32>("CHAR_WRAP").copied(),
+            embed_stylesheets: config.embed_stylesheets,
+            architecture_filename: config
+                .architecture_view
+                .as_ref()
+                .and_then(|p| get_filename(p)),
+            evidence_filename: config.evidence.as_ref().and_then(|p| get_filename(p)),
+            complete_filename: config.complete_view.as_ref().and_then(|p| get_filename(p)),
+            output_directory: &config.output_directory,
+            skip_argument: config.no_argument_view,
+            char_wrap: config.char_wrap,
         }
     }
 }
 
 ///
-/// Get RenderLegend form ArgMatches
+/// Get RenderLegend from configuration
 ///
-fn get_render_legend(matches: &ArgMatches) -> RenderLegend {
-    if matches.get_flag("NO_LEGEND") {
+fn get_render_legend(config: &Config) -> RenderLegend {
+    if config.no_legend {
         RenderLegend::No
-    } else if matches.get_flag("FULL_LEGEND") {
+    } else if config.full_legend {
         RenderLegend::Full
     } else {
         RenderLegend::Short
@@ -140,6 +113,14 @@ pub fn svg_from_gsn_node(
         GsnNodeType::CounterSolution => {
             SvgNode::new_counter_solution(identifier, gsn_node, masked, layers, char_wrap)
         }
+        GsnNodeType::Module => SvgNode::new_module(
+            identifier,
+            gsn_node,
+            masked,
+            layers,
+            gsn_node.url.to_owned(),
+            char_wrap,
+        ),
     }
 }
 
@@ -164,8 +145,11 @@ pub fn away_svg_from_gsn_node(
             module.output_path.as_ref().unwrap(), // unwrap ok, since output_path is set initially.
             source_module.output_path.as_ref().unwrap(), // unwrap ok, since output_path is set initially.
         );
-        x.push('#');
-        x.push_str(&escape_node_id(identifier));
+        // Module references link to the module itself
+        if gsn_node.node_type != Some(GsnNodeType::Module) {
+            x.push('#');
+            x.push_str(&escape_node_id(identifier));
+        }
         Some(x)
     };
     // Create node
@@ -189,6 +173,9 @@ pub fn away_svg_from_gsn_node(
         GsnNodeType::Justification => SvgNode::new_away_justification(
             identifier, gsn_node, masked, layers, module_url, char_wrap,
         ),
+        GsnNodeType::Module => {
+            SvgNode::new_module(identifier, gsn_node, masked, layers, module_url, char_wrap)
+        }
         _ => unreachable!(),
     })
 }
@@ -223,16 +210,22 @@ pub fn render_architecture(
                 module.output_path.as_ref().unwrap(),
                 architecture_path,
             ));
+            let masked = render_options.masked_elements.contains(&module.meta.name);
+            let char_wrap = module.meta.char_wrap.or(render_options.char_wrap);
             Ok((
                 k.to_owned(),
-                SvgNode::new_module(
-                    k,
-                    &module_node,
-                    render_options.masked_elements.contains(&module.meta.name),
-                    &[],
-                    module_url,
-                    module.meta.char_wrap.or(render_options.char_wrap),
-                ),
+                if module.meta.contract.is_empty() {
+                    SvgNode::new_module(k, &module_node, masked, &[], module_url, char_wrap)
+                } else {
+                    SvgNode::new_contract_module(
+                        k,
+                        &module_node,
+                        masked,
+                        &[],
+                        module_url,
+                        char_wrap,
+                    )
+                },
             ))
         })
         .collect::<Result<BTreeMap<String, SvgNode>>>()?;
@@ -334,9 +327,12 @@ pub fn render_argument<'a>(
     render_options: &RenderOptions,
 ) -> Result<()> {
     let mut dg = crate::dirgraphsvg::DirGraph::default();
+    // A module is not shown as reference in its own argument view
+    let is_own_module_reference =
+        |node: &GsnNode| node.node_type == Some(GsnNodeType::Module) && node.module == module_name;
     let mut svg_nodes: BTreeMap<String, SvgNode> = nodes
         .iter()
-        .filter(|(_, node)| node.module == module_name)
+        .filter(|(_, node)| node.module == module_name && !is_own_module_reference(node))
         .map(|(id, node)| {
             (
                 id.to_owned(),
@@ -381,15 +377,17 @@ pub fn render_argument<'a>(
 
     let edges: BTreeMap<String, Vec<(String, EdgeType<'a>)>> = nodes
         .iter()
+        .filter(|(_, node)| !is_own_module_reference(node))
         .map(|(id, node)| {
             (
                 id.to_owned(),
                 node.get_edges()
                     .into_iter()
                     .filter(|(target, _)| {
-                        !(node.module != module_name
-                            // unwrap is ok, since all references are checked at the beginning
-                            && nodes.get(target).unwrap().module != module_name)
+                        // unwrap is ok, since all references are checked at the beginning
+                        let target_node = nodes.get(target).unwrap();
+                        (node.module == module_name || target_node.module == module_name)
+                            && !is_own_module_reference(target_node)
                     })
                     .map(|(s, t)| (s.to_owned(), EdgeType::from(t)))
                     .collect::<Vec<(String, EdgeType<'a>)>>(),
@@ -433,34 +431,58 @@ pub fn render_argument<'a>(
     }
 
     // Create ACPs as edge decorators from node information.
-    let mut acps: BTreeMap<(String, String), EdgeDecorator> = BTreeMap::new();
+    let mut decorators: BTreeMap<(String, String), Vec<EdgeDecorator>> = BTreeMap::new();
     nodes.iter().for_each(|(s, n)| {
         n.acp.iter().for_each(|(acp, ts)| {
             ts.iter().filter(|&t| s != t).for_each(|t| {
-                acps.entry((s.to_owned(), t.to_owned()))
-                    .and_modify(|a: &mut EdgeDecorator| match a {
-                        EdgeDecorator::Acps(items) => {
-                            if !items.contains(acp) {
-                                items.push(acp.to_owned());
-                            }
-                        }
-                        EdgeDecorator::Defeated => unreachable!(),
-                    })
-                    .or_insert_with(|| EdgeDecorator::Acps(vec![acp.to_owned()]));
+                let edge_decorators = decorators.entry((s.to_owned(), t.to_owned())).or_default();
+                if let Some(EdgeDecorator::Acps(items)) = edge_decorators
+                    .iter_mut()
+                    .find(|d| matches!(d, EdgeDecorator::Acps(_)))
+                {
+                    if !items.contains(acp) {
+                        items.push(acp.to_owned());
+                    }
+                } else {
+                    edge_decorators.push(EdgeDecorator::Acps(vec![acp.to_owned()]));
+                }
             });
         })
     });
-    let mut defeated_relations: BTreeMap<(String, String), EdgeDecorator> = nodes
-        .iter()
-        .flat_map(|(s, n)| {
-            n.defeated_relation
-                .iter()
-                .map(|t| ((s.to_owned(), t.to_owned()), EdgeDecorator::Defeated))
+    nodes.iter().for_each(|(s, n)| {
+        n.defeated_relation.iter().for_each(|t| {
+            decorators
+                .entry((s.to_owned(), t.to_owned()))
+                .or_default()
+                .push(EdgeDecorator::Defeated)
         })
-        .collect();
-    acps.append(&mut defeated_relations);
+    });
+    // Create multiplicity, optionality and choice of argument patterns as edge decorators.
+    // The choice is only drawn once at the first alternative, since all alternatives start at the same point.
+    let mut choices = BTreeSet::new();
+    nodes.iter().for_each(|(s, n)| {
+        n.get_edges().into_iter().for_each(|(t, e)| {
+            if let GsnEdgeType::SupportedByPattern(pattern)
+            | GsnEdgeType::InContextOfPattern(pattern) = e
+            {
+                let edge_decorators = decorators.entry((s.to_owned(), t)).or_default();
+                match pattern.multiplicity {
+                    Some(Multiplicity::Multiple(label)) => {
+                        edge_decorators.push(EdgeDecorator::Multiplicity(label.to_owned()))
+                    }
+                    Some(Multiplicity::Optional) => edge_decorators.push(EdgeDecorator::Optional),
+                    None => (),
+                }
+                if let Some(choice) = pattern.choice
+                    && choices.insert((s, choice))
+                {
+                    edge_decorators.push(EdgeDecorator::Choice(choice.to_owned()));
+                }
+            }
+        })
+    });
 
-    dg.write(svg_nodes, edges, output, acps)?;
+    dg.write(svg_nodes, edges, output, decorators)?;
 
     Ok(())
 }
@@ -468,9 +490,7 @@ pub fn render_argument<'a>(
 #[cfg(test)]
 mod test {
 
-    use clap::{Arg, ArgAction, Command};
-
-    use crate::{gsn::GsnNode, render::RenderLegend};
+    use crate::{config::Config, gsn::GsnNode, render::RenderLegend};
 
     use super::{get_render_legend, svg_from_gsn_node};
 
@@ -482,20 +502,13 @@ mod test {
     }
 
     #[test]
-    fn translate_render_legend() -> anyhow::Result<()> {
-        let cmd = Command::new("gsn2x")
-            .arg(Arg::new("NO_LEGEND").short('G').action(ArgAction::SetTrue))
-            .arg(
-                Arg::new("FULL_LEGEND")
-                    .short('g')
-                    .action(ArgAction::SetTrue),
-            );
-        let matches = cmd.clone().try_get_matches_from(vec!["gsn2x"])?;
-        assert_eq!(get_render_legend(&matches), RenderLegend::Short);
-        let matches = cmd.clone().try_get_matches_from(vec!["gsn2x", "-G"])?;
-        assert_eq!(get_render_legend(&matches), RenderLegend::No);
-        let matches = cmd.clone().try_get_matches_from(vec!["gsn2x", "-g"])?;
-        assert_eq!(get_render_legend(&matches), RenderLegend::Full);
-        Ok(())
+    fn translate_render_legend() {
+        let mut config = Config::default();
+        assert_eq!(get_render_legend(&config), RenderLegend::Short);
+        config.no_legend = true;
+        assert_eq!(get_render_legend(&config), RenderLegend::No);
+        config.no_legend = false;
+        config.full_legend = true;
+        assert_eq!(get_render_legend(&config), RenderLegend::Full);
     }
 }
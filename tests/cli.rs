@@ -384,6 +384,851 @@ fn confidence_extension() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn pattern_extension() -> Result<()> {
+    regression_renderings(
+        &["examples/pattern/hazard_avoidance.gsn.yaml"],
+        &["-E"],
+        None,
+    )?;
+    Ok(())
+}
+
+#[test]
+fn pattern_annotation_locations() -> Result<()> {
+    let temp = assert_fs::TempDir::new()?;
+    temp.child("index.gsn.yaml").write_str(
+        "G1:\n  text: Goal\n  supportedBy: [G2, G3]\n  acp:\n    ACP1: [G4]\n  multiplicity:\n    G2: 2\n  optional: G2\n  choice:\n    1 of 1: G2\n    1 of 2: [G2, G3]\n\nG2:\n  text: Goal\n  undeveloped: true\n\nG3:\n  text: Goal\n  undeveloped: true\n",
+    )?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("-c").arg("index.gsn.yaml").current_dir(&temp);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "index.gsn.yaml:5:12: Error: (index_gsn_yaml) V09: Element G1 has an assurance claim point ACP1 that references G4",
+        ))
+        .stderr(predicate::str::contains(
+            "index.gsn.yaml:10:5: Error: (index_gsn_yaml) V15: Choice 1 of 1 of element G1",
+        ))
+        .stderr(predicate::str::contains(
+            "index.gsn.yaml:7:5: Error: (index_gsn_yaml) V16: Relation from G1 to G2",
+        ))
+        .stderr(predicate::str::contains(
+            "index.gsn.yaml:11:14: Error: (index_gsn_yaml) V19: Relation from G1 to G2",
+        ));
+    temp.close()?;
+    Ok(())
+}
+
+#[test]
+fn module_interface() -> Result<()> {
+    regression_renderings(
+        &[
+            "examples/interface/system.gsn.yaml",
+            "examples/interface/component.gsn.yaml",
+        ],
+        &["-E", "-A", "-F"],
+        None,
+    )?;
+    Ok(())
+}
+
+#[test]
+fn module_interface_private_reference() -> Result<()> {
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("-c").arg("tests/private_reference.yaml");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "tests/private_reference.yaml:7:17: Error: (PrivateReference) C19: Element G1 references element G3 of module Component, but it is not public.",
+        ));
+    Ok(())
+}
+
+#[test]
+fn module_interface_with_extension() -> Result<()> {
+    let temp = assert_fs::TempDir::new()?;
+    temp.child("template.gsn.yaml").write_str(
+        "module:\n  name: template\n  brief: Template\n\nG1:\n  text: Goal\n  undeveloped: true\n",
+    )?;
+    temp.child("instance.gsn.yaml").write_str(
+        "module:\n  name: instance\n  brief: Instance\n  uses: [template.gsn.yaml]\n  extends:\n    - module: template\n      develops:\n        G1: [G2]\n\nG2:\n  text: Private goal\n  supportedBy: [Sn1]\n\nSn1:\n  text: Solution\n\nG3:\n  text: Public goal\n  public: true\n  undeveloped: true\n",
+    )?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("-c").arg("instance.gsn.yaml").current_dir(&temp);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("C19").not());
+    temp.close()?;
+    Ok(())
+}
+
+#[test]
+fn module_reference() -> Result<()> {
+    regression_renderings(
+        &["examples/module_reference/system.gsn.yaml"],
+        &["-E", "-A", "-F"],
+        Some(&["examples/module_reference/hazards.gsn.yaml"]),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn suppressed_findings() -> Result<()> {
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("-c")
+        .arg("--extended-check")
+        .arg("tests/suppression.yaml");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("2 findings suppressed."))
+        .stderr(predicate::str::contains("C01").not())
+        .stderr(predicate::str::contains("V08").not());
+    Ok(())
+}
+
+#[test]
+fn suppressed_errors_do_not_stop_processing() -> Result<()> {
+    let temp = assert_fs::TempDir::new()?;
+    temp.child("index.gsn.yaml").write_str(
+        "module:\n  name: main\n  brief: Main\n  uses: [missing.gsn.yaml]\n  suppress: [C21]\n\nG1:\n  text: Goal\n  supportedBy: [Sn1, Sn2]\n  suppress: [C03]\n\nSn1:\n  text: Solution\n",
+    )?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("index.gsn.yaml").current_dir(&temp);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Rendering \"./index.gsn.svg\": OK",
+        ))
+        .stderr(predicate::str::contains("2 findings suppressed."))
+        .stderr(predicate::str::contains("Error").not());
+    temp.close()?;
+    Ok(())
+}
+
+#[test]
+fn suppressed_structural_errors_stop_processing() -> Result<()> {
+    let temp = assert_fs::TempDir::new()?;
+    temp.child("index.gsn.yaml").write_str(
+        "module:\n  name: main\n  brief: Main\n  suppress: [V01, V04]\n\nG1:\n  text: Goal\n  inContextOf: [X1]\n  undeveloped: true\n\nX1:\n  text: Unknown type\n",
+    )?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("index.gsn.yaml").current_dir(&temp);
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "Error: Processing stopped, since 2 findings of structural checks (V01, V04, C12, C13, C14) are not reported as errors, but leave elements that cannot be processed further. 2 findings suppressed.",
+        ))
+        .stderr(predicate::str::contains("panicked").not());
+    temp.child("index.gsn.svg")
+        .assert(predicate::path::missing());
+    temp.close()?;
+    Ok(())
+}
+
+#[test]
+fn diagnostics_json_lines() -> Result<()> {
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("-c")
+        .arg("--diagnostics=jsonl")
+        .arg("tests/private_reference.yaml");
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            r#"{"check":"C19","elements":["G1","G3"],"location":{"column":17,"file":"tests/private_reference.yaml","line":7},"message":"Element G1 references element G3 of module Component, but it is not public.","module":"PrivateReference","severity":"error"}"#,
+        ))
+        .stderr(predicate::str::contains("C19").not());
+    Ok(())
+}
+
+#[test]
+fn diagnostics_sarif() -> Result<()> {
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    let temp = assert_fs::TempDir::new()?;
+    temp.copy_from(".", &["tests/private_reference.yaml"])?;
+    temp.copy_from(".", &["examples/interface/*.yaml"])?;
+    cmd.arg("-c")
+        .arg("--diagnostics=sarif")
+        .arg("--diagnostics-output=report.sarif")
+        .arg("tests/private_reference.yaml")
+        .current_dir(&temp);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Error: 1 errors and 1 warnings detected.",
+    ));
+    let sarif: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(temp.child("report.sarif").path())?)?;
+    assert_eq!(sarif["version"], "2.1.0");
+    let results = sarif["runs"][0]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["ruleId"], "C19");
+    assert_eq!(results[0]["level"], "error");
+    assert_eq!(
+        results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+        "tests/private_reference.yaml"
+    );
+    assert_eq!(
+        results[0]["locations"][0]["physicalLocation"]["region"]["startLine"],
+        7
+    );
+    assert_eq!(results[0]["properties"]["module"], "PrivateReference");
+    temp.close()?;
+    Ok(())
+}
+
+#[test]
+fn json_schema() -> Result<()> {
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("--schema");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#""$schema": "https://json-schema.org/draft/2020-12/schema""#,
+        ))
+        .stdout(predicate::str::contains(r#""GsnNode": {"#));
+    Ok(())
+}
+
+#[test]
+fn project_file() -> Result<()> {
+    let temp = assert_fs::TempDir::new()?;
+    temp.copy_from(".", &["examples/interface/*.yaml"])?;
+    temp.child("gsn2x.yaml").write_str(
+        "inputs: [examples/interface/component.gsn.yaml]\nno-arch: true\nno-evidence: true\nno-full: true\noutput-dir: out\n",
+    )?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.current_dir(&temp);
+    cmd.assert().success();
+    temp.child("out/examples/interface/component.gsn.svg")
+        .assert(predicate::path::exists());
+    temp.child("out/architecture.svg")
+        .assert(predicate::path::missing());
+    // Command line overrides project file
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("--deny-warnings").arg("-c").current_dir(&temp);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "1 errors and 0 warnings detected.",
+    ));
+    temp.close()?;
+    Ok(())
+}
+
+#[test]
+fn unresolved_reference_unused_file() -> Result<()> {
+    let temp = assert_fs::TempDir::new()?;
+    temp.child("main.yaml").write_str(
+        "module:\n  name: main\n  brief: Main\n\nG1:\n  text: Goal\n  supportedBy: [S1]\n",
+    )?;
+    temp.child("sub.yaml").write_str(
+        "module:\n  name: sub\n  brief: Sub\n\nS1:\n  text: Strategy\n  undeveloped: true\n",
+    )?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("-c").arg("main.yaml").current_dir(&temp);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Error: (main) C03: Element G1 has unresolved \"supported by\" element: S1. Element S1 exists in sub.yaml, but that file is not used. Add it to `uses` of module main.",
+    ));
+    temp.close()?;
+    Ok(())
+}
+
+#[test]
+fn fix_findings() -> Result<()> {
+    let temp = assert_fs::TempDir::new()?;
+    let input = temp.child("fix.yaml");
+    input.write_str(
+        "# Fix me\nG1:\n  text: Goal\n  supportedBy: S1, S2 # comma\n  inContextOf: [C1, C1]\n\nS1:\n  text: Strategy\n  supportedBy: [G2]\n\nG2:\n  text: Sub goal\n\nS2:\n  text: Strategy 2\n  undeveloped: true\n\nC1:\n  text: Context\n",
+    )?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("--fix")
+        .arg("-c")
+        .arg("fix.yaml")
+        .current_dir(&temp);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "fix.yaml: Fixed G1: Changed supportedBy to the list [S1, S2].",
+        ))
+        .stderr(predicate::str::contains(
+            "fix.yaml: Fixed G1: Removed duplicate entry C1 from inContextOf.",
+        ))
+        .stderr(predicate::str::contains(
+            "fix.yaml: Fixed G2: Added undeveloped: true.",
+        ))
+        .stderr(predicate::str::contains("3 findings fixed."))
+        .stderr(predicate::str::contains("Warning").not());
+    input.assert(
+        "# Fix me\nG1:\n  text: Goal\n  supportedBy: [S1, S2] # comma\n  inContextOf: [C1]\n\nS1:\n  text: Strategy\n  supportedBy: [G2]\n\nG2:\n  text: Sub goal\n  undeveloped: true\n\nS2:\n  text: Strategy 2\n  undeveloped: true\n\nC1:\n  text: Context\n",
+    );
+    temp.close()?;
+    Ok(())
+}
+
+#[test]
+fn format_inputs() -> Result<()> {
+    let temp = assert_fs::TempDir::new()?;
+    let input = temp.child("format.yaml");
+    input.write_str(
+        "G1:\n    supportedBy:\n      - S1 # strategy\n    text: Goal\n\n\nS1: \n    undeveloped: true\n    text: Strategy\n",
+    )?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("--fmt")
+        .arg("--check")
+        .arg("format.yaml")
+        .current_dir(&temp);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("format.yaml: Not formatted."));
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("--fmt").arg("format.yaml").current_dir(&temp);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("format.yaml: Formatted."));
+    input.assert(
+        "G1:\n  text: Goal\n  supportedBy: [S1] # strategy\n\nS1:\n  text: Strategy\n  undeveloped: true\n",
+    );
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("--fmt")
+        .arg("--check")
+        .arg("format.yaml")
+        .current_dir(&temp);
+    cmd.assert().success().stderr(predicate::str::is_empty());
+    temp.close()?;
+    Ok(())
+}
+
+#[test]
+fn rename_element() -> Result<()> {
+    let temp = assert_fs::TempDir::new()?;
+    let main = temp.child("main.gsn.yaml");
+    main.write_str(
+        "module:\n  name: main\n  brief: Main\n  uses: [sub.gsn.yaml]\n\nG1:\n  text: Goal\n  supportedBy: [S1]\n\nS1:\n  text: Strategy\n  supportedBy: [G2] # sub goal\n",
+    )?;
+    let sub = temp.child("sub.gsn.yaml");
+    sub.write_str(
+        "module:\n  name: sub\n  brief: Sub\n\nG2:\n  text: Sub goal\n  supportedBy: [Sn1]\n\nSn1:\n  text: Solution\n",
+    )?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("--rename=G2=G1")
+        .arg("main.gsn.yaml")
+        .current_dir(&temp);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Cannot rename G2 to G1, since G1 is already used.",
+    ));
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("--rename=G2=S2")
+        .arg("main.gsn.yaml")
+        .current_dir(&temp);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Cannot rename G2 to S2, since the type implied by the ID would change from Goal to Strategy.",
+    ));
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("--rename=G2=G3")
+        .arg("main.gsn.yaml")
+        .current_dir(&temp);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "main.gsn.yaml: Renamed G2 to G3 (1 occurrences).",
+        ))
+        .stderr(predicate::str::contains(
+            "sub.gsn.yaml: Renamed G2 to G3 (1 occurrences).",
+        ));
+    main.assert(
+        "module:\n  name: main\n  brief: Main\n  uses: [sub.gsn.yaml]\n\nG1:\n  text: Goal\n  supportedBy: [S1]\n\nS1:\n  text: Strategy\n  supportedBy: [G3] # sub goal\n",
+    );
+    sub.assert(
+        "module:\n  name: sub\n  brief: Sub\n\nG3:\n  text: Sub goal\n  supportedBy: [Sn1]\n\nSn1:\n  text: Solution\n",
+    );
+    main.write_str(
+        "module:\n  name: main\n  brief: Main\n  uses: [sub.gsn.yaml]\n\nG1:\n  text: Goal\n  supportedBy: [S1]\n\nS1: {text: Strategy, supportedBy: [G3]}\n",
+    )?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("--rename=G3=G4")
+        .arg("main.gsn.yaml")
+        .current_dir(&temp);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Cannot rename elements in file main.gsn.yaml, since element S1 could not be edited. Only elements written as block mappings are supported. No file was modified.",
+    ));
+    main.assert(
+        "module:\n  name: main\n  brief: Main\n  uses: [sub.gsn.yaml]\n\nG1:\n  text: Goal\n  supportedBy: [S1]\n\nS1: {text: Strategy, supportedBy: [G3]}\n",
+    );
+    sub.assert(
+        "module:\n  name: sub\n  brief: Sub\n\nG3:\n  text: Sub goal\n  supportedBy: [Sn1]\n\nSn1:\n  text: Solution\n",
+    );
+    temp.close()?;
+    Ok(())
+}
+
+#[test]
+fn renumber_module() -> Result<()> {
+    let temp = assert_fs::TempDir::new()?;
+    let main = temp.child("main.gsn.yaml");
+    main.write_str(
+        "module:\n  name: main\n  brief: Main\n  uses: [sub.gsn.yaml]\n\nG10:\n  text: Goal\n  supportedBy: [S3]\n\nS3:\n  text: Strategy\n  supportedBy: [G2, G7]\n",
+    )?;
+    let sub = temp.child("sub.gsn.yaml");
+    sub.write_str(
+        "module:\n  name: sub\n  brief: Sub\n\nG2:\n  text: Sub goal\n  supportedBy: [Sn1]\n\nG7:\n  text: Other sub goal\n  undeveloped: true\n\nSn1:\n  text: Solution\n",
+    )?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("--renumber=main")
+        .arg("main.gsn.yaml")
+        .current_dir(&temp);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "main.gsn.yaml: Renumbered elements of main (3 occurrences).",
+        ))
+        .stderr(predicate::str::contains(
+            "2 elements renumbered. Mapping written to ./renumbering.yaml.",
+        ));
+    main.assert(
+        "module:\n  name: main\n  brief: Main\n  uses: [sub.gsn.yaml]\n\nG1:\n  text: Goal\n  supportedBy: [S1]\n\nS1:\n  text: Strategy\n  supportedBy: [G2, G7]\n",
+    );
+    temp.child("renumbering.yaml").assert("G10: G1\nS3: S1\n");
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("--renumber=sub")
+        .arg("main.gsn.yaml")
+        .current_dir(&temp);
+    cmd.assert().success();
+    main.assert(
+        "module:\n  name: main\n  brief: Main\n  uses: [sub.gsn.yaml]\n\nG1:\n  text: Goal\n  supportedBy: [S1]\n\nS1:\n  text: Strategy\n  supportedBy: [G2, G3]\n",
+    );
+    sub.assert(
+        "module:\n  name: sub\n  brief: Sub\n\nG2:\n  text: Sub goal\n  supportedBy: [Sn1]\n\nG3:\n  text: Other sub goal\n  undeveloped: true\n\nSn1:\n  text: Solution\n",
+    );
+    temp.child("renumbering.yaml").assert("G7: G3\n");
+    std::fs::remove_file(temp.child("renumbering.yaml"))?;
+    sub.write_str(
+        "module:\n  name: sub\n  brief: Sub\n\nG2: {text: Sub goal, supportedBy: [Sn4]}\n\nG3:\n  text: Other sub goal\n  undeveloped: true\n\nSn4:\n  text: Solution\n",
+    )?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("--renumber=sub")
+        .arg("main.gsn.yaml")
+        .current_dir(&temp);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Cannot rename elements in file sub.gsn.yaml, since element G2 could not be edited.",
+    ));
+    sub.assert(
+        "module:\n  name: sub\n  brief: Sub\n\nG2: {text: Sub goal, supportedBy: [Sn4]}\n\nG3:\n  text: Other sub goal\n  undeveloped: true\n\nSn4:\n  text: Solution\n",
+    );
+    temp.child("renumbering.yaml")
+        .assert(predicate::path::missing());
+    temp.close()?;
+    Ok(())
+}
+
+#[test]
+fn json_and_yaml_inputs() -> Result<()> {
+    let temp = assert_fs::TempDir::new()?;
+    temp.child("main.gsn.yaml").write_str(
+        "module:\n  name: main\n  brief: Main\n  uses: [sub.gsn.json]\n\nG1:\n  text: Goal\n  supportedBy: [S1]\n",
+    )?;
+    let sub = temp.child("sub.gsn.json");
+    sub.write_str(
+        "{\n  \"module\": {\"name\": \"sub\", \"brief\": \"Sub\", \"uses\": [\"leaf.gsn.yaml\"]},\n  \"S1\": {\n    \"text\": \"Strategy\",\n    \"supportedBy\": [\"G2\", \"G3\"]\n  }\n}\n",
+    )?;
+    temp.child("leaf.gsn.yaml").write_str(
+        "module:\n  name: leaf\n  brief: Leaf\n\nG2:\n  text: Sub goal\n  undeveloped: true\n",
+    )?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("-c").arg("main.gsn.yaml").current_dir(&temp);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "sub.gsn.json:5:27: Error: (sub) C03: Element S1 has unresolved \"supported by\" element: G3",
+    ));
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("--rename=G2=G3")
+        .arg("main.gsn.yaml")
+        .current_dir(&temp);
+    cmd.assert().success().stderr(predicate::str::contains(
+        "sub.gsn.json: Renamed G2 to G3 (1 occurrences).",
+    ));
+    sub.assert(
+        "{\n  \"module\": {\"name\": \"sub\", \"brief\": \"Sub\", \"uses\": [\"leaf.gsn.yaml\"]},\n  \"S1\": {\n    \"text\": \"Strategy\",\n    \"supportedBy\": [\"G3\", \"G3\"]\n  }\n}\n",
+    );
+    sub.write_str("{\n  \"S1\": {\"text\": \"Strategy\",}\n}\n")?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("-c").arg("main.gsn.yaml").current_dir(&temp);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Failed to parse JSON from file sub.gsn.json",
+    ));
+    temp.close()?;
+    Ok(())
+}
+
+#[test]
+fn several_modules_in_one_file() -> Result<()> {
+    let temp = assert_fs::TempDir::new()?;
+    temp.child("index.gsn.yaml").write_str(
+        "module:\n  name: main\n  brief: Main\n  uses: [suppliers.gsn.yaml]\n\nG1:\n  text: Goal\n  supportedBy: [S1]\n\nS1:\n  text: Strategy\n  supportedBy: [G2, G3]\n",
+    )?;
+    let suppliers = temp.child("suppliers.gsn.yaml");
+    suppliers.write_str(
+        "module:\n  name: supplierA\n  brief: Supplier A\n\nG2:\n  text: A\n  undeveloped: true\n---\nmodule:\n  name: supplierB\n  brief: Supplier B\n\nG3:\n  text: B\n  undeveloped: true\n",
+    )?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("index.gsn.yaml")
+        .arg("-A")
+        .arg("-F")
+        .current_dir(&temp);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Rendering \"./suppliers.gsn.1.svg\": OK",
+        ))
+        .stdout(predicate::str::contains(
+            "Rendering \"./suppliers.gsn.2.svg\": OK",
+        ));
+    suppliers.write_str(
+        "module:\n  name: supplierA\n  brief: Supplier A\n\nG2:\n  text: A\n  undeveloped: true\n---\nmodule:\n  name: supplierB\n  brief: Supplier B\n\nG2:\n  text: B\n  undeveloped: true\n",
+    )?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("-c").arg("index.gsn.yaml").current_dir(&temp);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "suppliers.gsn.yaml:13:1: Error: (supplierB) C07: Element G2 in suppliers.gsn.yaml (document 2) was already present in supplierA.",
+    ));
+    suppliers.write_str(
+        "module:\n  name: supplierA\n  brief: Supplier A\n\nG2:\n  text: A\n  undeveloped: true\n---\nmodule:\n  name: supplierA\n  brief: Supplier B\n\nG3:\n  text: B\n  undeveloped: true\n",
+    )?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("-c").arg("index.gsn.yaml").current_dir(&temp);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "C06: Module in suppliers.gsn.yaml (document 2) was already present in suppliers.gsn.yaml (document 1)",
+    ));
+    temp.close()?;
+    Ok(())
+}
+
+#[test]
+fn include_directories() -> Result<()> {
+    let temp = assert_fs::TempDir::new()?;
+    temp.child("project/index.gsn.yaml").write_str(
+        "module:\n  name: main\n  brief: Main\n  uses: [common.gsn.yaml]\n\nG1:\n  text: Goal\n  supportedBy: [G2]\n",
+    )?;
+    temp.child("lib/common.gsn.yaml").write_str(
+        "module:\n  name: common\n  brief: Common\n\nG2:\n  text: Common goal\n  undeveloped: true\n",
+    )?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("-c")
+        .arg("project/index.gsn.yaml")
+        .current_dir(&temp);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "C21: Used file common.gsn.yaml is not found. Tried project/common.gsn.yaml.",
+    ));
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("-I=lib")
+        .arg("project/index.gsn.yaml")
+        .arg("-A")
+        .current_dir(&temp);
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Rendering \"./lib/common.gsn.svg\": OK",
+    ));
+    temp.child("project/index.gsn.svg")
+        .assert(predicate::str::contains("../lib/common.gsn.svg#node_g2"));
+    // A missing file does not prevent reading the files that are found.
+    temp.child("project/index.gsn.yaml").write_str(
+        "module:\n  name: main\n  brief: Main\n  uses: [missing.gsn.yaml, common.gsn.yaml]\n\nG1:\n  text: Goal\n  supportedBy: [G2]\n",
+    )?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("-c")
+        .arg("-I=lib")
+        .arg("project/index.gsn.yaml")
+        .current_dir(&temp);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "C21: Used file missing.gsn.yaml is not found. Tried project/missing.gsn.yaml, lib/missing.gsn.yaml.",
+        ))
+        .stderr(predicate::str::contains("C03").not())
+        .stderr(predicate::str::contains(
+            "Error: 1 errors and 0 warnings detected.",
+        ));
+    temp.close()?;
+    Ok(())
+}
+
+#[test]
+fn all_input_problems() -> Result<()> {
+    let temp = assert_fs::TempDir::new()?;
+    temp.child("index.gsn.yaml").write_str(
+        "module:\n  name: main\n  brief: Main\n  uses: [a.gsn.yaml, b.gsn.json]\n\nG1:\n  text: Goal\n  supportedBy: [G2, G3]\n\nG4:\n  text: Goal\n  undeveloped: true\n",
+    )?;
+    temp.child("a.gsn.yaml")
+        .write_str("G2:\n  text: A\n  - B\n")?;
+    temp.child("b.gsn.json")
+        .write_str("{\"G3\": {\"text\": \"B\",}}\n")?;
+    temp.child("c.gsn.yaml").write_str(
+        "G1:\n  text: Goal\n  undeveloped: true\n\nG4:\n  text: Goal\n  undeveloped: true\n\nG5:\n  text: Goal\n  undeveloped: true\n",
+    )?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("-c")
+        .arg("index.gsn.yaml")
+        .arg("c.gsn.yaml")
+        .current_dir(&temp);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "a.gsn.yaml:3:5: Error: Failed to parse YAML from file a.gsn.yaml",
+        ))
+        .stderr(predicate::str::contains(
+            "b.gsn.json:1:21: Error: Failed to parse JSON from file b.gsn.json",
+        ))
+        .stderr(predicate::str::contains(
+            "c.gsn.yaml:1:1: Error: (c_gsn_yaml) C07: Element G1 in c.gsn.yaml was already present in main.",
+        ))
+        .stderr(predicate::str::contains(
+            "c.gsn.yaml:5:1: Error: (c_gsn_yaml) C07: Element G4 in c.gsn.yaml was already present in main.",
+        ))
+        .stderr(predicate::str::contains(
+            "Error: (main) C03: Element G1 has unresolved \"supported by\" element: G2",
+        ));
+    temp.close()?;
+    Ok(())
+}
+
+#[test]
+fn used_files_are_read_despite_input_problems() -> Result<()> {
+    let temp = assert_fs::TempDir::new()?;
+    temp.child("main.yaml").write_str(
+        "module:\n  name: main\n  brief: Main\n  uses: [lib.yaml]\n\nG1:\n  text: Goal\n  supportedBy: [G2]\n",
+    )?;
+    temp.child("lib.yaml").write_str(
+        "module:\n  name: lib\n  brief: Library\n  uses: [base.yaml]\n\nG2:\n  text: Library goal\n  supportedBy: [G3]\n",
+    )?;
+    temp.child("base.yaml").write_str(
+        "module:\n  name: base\n  brief: Base\n\nG3:\n  text: Base goal\n  undeveloped: true\n",
+    )?;
+    temp.child("bad.yaml")
+        .write_str("G9:\n  text: Bad\n  - X\n")?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("-c")
+        .arg("main.yaml")
+        .arg("bad.yaml")
+        .current_dir(&temp);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "bad.yaml:3:5: Error: Failed to parse YAML from file bad.yaml",
+        ))
+        .stderr(predicate::str::contains("C03").not())
+        .stderr(predicate::str::contains(
+            "Error: 1 errors and 0 warnings detected.",
+        ));
+    temp.close()?;
+    Ok(())
+}
+
+#[test]
+fn declared_roots() -> Result<()> {
+    let temp = assert_fs::TempDir::new()?;
+    temp.child("hazards.gsn.yaml").write_str(
+        "module:\n  name: hazards\n  brief: Hazards\n  roots: [G1, G2]\n\nG1:\n  text: H1 is mitigated\n  supportedBy: [Sn1]\n  inContextOf: [C1]\n\nG2:\n  text: H2 is mitigated\n  supportedBy: [Sn2]\n  inContextOf: [C1]\n\nC1:\n  text: Hazard list\n\nSn1:\n  text: Test\n\nSn2:\n  text: Review\n\nG3:\n  text: Stray goal\n  undeveloped: true\n",
+    )?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("hazards.gsn.yaml").current_dir(&temp);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Rendering \"./hazards.gsn.G1.svg\": OK",
+        ))
+        .stdout(predicate::str::contains(
+            "Rendering \"./hazards.gsn.G2.svg\": OK",
+        ))
+        .stderr(predicate::str::contains(
+            "hazards.gsn.yaml:25:1: Warning: (hazards) C01: Element G3 is unreferenced, but not declared in the roots of module hazards.",
+        ));
+    temp.child("hazards.gsn.G2.svg")
+        .assert(predicate::str::contains("<title>Sn2</title>"))
+        .assert(predicate::str::contains("<title>Sn1</title>").not());
+    temp.close()?;
+    Ok(())
+}
+
+#[test]
+fn declared_roots_of_used_module() -> Result<()> {
+    let temp = assert_fs::TempDir::new()?;
+    temp.child("main.gsn.yaml").write_str(
+        "module:\n  name: main\n  brief: Main\n  uses: [lib.gsn.yaml]\n\nG1:\n  text: Goal\n  supportedBy: [G2]\n",
+    )?;
+    temp.child("lib.gsn.yaml").write_str(
+        "module:\n  name: lib\n  brief: Lib\n  roots: [G2]\n\nG2:\n  text: Lib goal\n  undeveloped: true\n",
+    )?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("-c").arg("main.gsn.yaml").current_dir(&temp);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("C01").not());
+    temp.close()?;
+    Ok(())
+}
+
+#[test]
+fn deny_warnings() -> Result<()> {
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("-c")
+        .arg("--deny-warnings")
+        .arg("examples/interface/component.gsn.yaml");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Error: C01: There is more than one unreferenced element: A2, G2.",
+        ))
+        .stderr(predicate::str::contains(
+            "1 errors and 0 warnings detected.",
+        ));
+    Ok(())
+}
+
+#[test]
+fn severity_policy() -> Result<()> {
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("-c")
+        .arg("--severity=C=warning")
+        .arg("tests/private_reference.yaml");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Warning: (PrivateReference) C19: Element G1",
+        ))
+        .stderr(predicate::str::contains("Warning: 2 warnings detected."));
+    Ok(())
+}
+
+#[test]
+fn demoted_errors_do_not_stop_processing() -> Result<()> {
+    let temp = assert_fs::TempDir::new()?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("--severity=C19=warning")
+        .arg(format!("-o={}", temp.path().display()))
+        .arg("tests/private_reference.yaml");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("private_reference.svg\": OK"))
+        .stderr(predicate::str::contains(
+            "Warning: (PrivateReference) C19: Element G1",
+        ))
+        .stderr(predicate::str::contains("Processing stopped").not());
+    temp.child("tests/private_reference.svg")
+        .assert(predicate::path::exists());
+    temp.close()?;
+    Ok(())
+}
+
+#[test]
+fn demoted_structural_errors_stop_processing() -> Result<()> {
+    let temp = assert_fs::TempDir::new()?;
+    temp.child("untyped.gsn.yaml").write_str(
+        "G1:\n  text: Goal\n  inContextOf: [X1]\n  undeveloped: true\n\nX1:\n  text: Unknown type\n",
+    )?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("--severity=V=warning")
+        .arg("untyped.gsn.yaml")
+        .current_dir(&temp);
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "Warning: (untyped_gsn_yaml) V01: Element X1 is of unknown type.",
+        ))
+        .stderr(predicate::str::contains(
+            "Error: Processing stopped, since 2 findings of structural checks (V01, V04, C12, C13, C14) are not reported as errors",
+        ))
+        .stderr(predicate::str::contains("panicked").not());
+    temp.child("untyped.gsn.svg")
+        .assert(predicate::path::missing());
+    temp.close()?;
+    Ok(())
+}
+
+#[test]
+fn baseline() -> Result<()> {
+    let temp = assert_fs::TempDir::new()?;
+    temp.copy_from(".", &["tests/private_reference.yaml"])?;
+    temp.copy_from(".", &["examples/interface/*.yaml"])?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("-c")
+        .arg("--write-baseline=baseline.json")
+        .arg("tests/private_reference.yaml")
+        .current_dir(&temp);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Error: 1 errors and 1 warnings detected.",
+    ));
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("-c")
+        .arg("--baseline=baseline.json")
+        .arg("tests/private_reference.yaml")
+        .current_dir(&temp);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("2 findings in baseline."))
+        .stderr(predicate::str::contains("C19").not());
+    // Baselined errors do not stop the generation of outputs.
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("--baseline=baseline.json")
+        .arg("-o=out")
+        .arg("tests/private_reference.yaml")
+        .current_dir(&temp);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("2 findings in baseline."));
+    temp.child("out/tests/private_reference.svg")
+        .assert(predicate::path::exists());
+    temp.close()?;
+    Ok(())
+}
+
+#[test]
+fn baselined_structural_errors_stop_processing() -> Result<()> {
+    let temp = assert_fs::TempDir::new()?;
+    temp.child("untyped.gsn.yaml").write_str(
+        "G1:\n  text: Goal\n  inContextOf: [X1]\n  undeveloped: true\n\nX1:\n  text: Unknown type\n",
+    )?;
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("-c")
+        .arg("--write-baseline=b.json")
+        .arg("untyped.gsn.yaml")
+        .current_dir(&temp);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Error: 2 errors and 0 warnings detected.",
+    ));
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("--baseline=b.json")
+        .arg("untyped.gsn.yaml")
+        .current_dir(&temp);
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "Error: Processing stopped, since 2 findings of structural checks (V01, V04, C12, C13, C14) are not reported as errors, but leave elements that cannot be processed further. 2 findings in baseline.",
+        ))
+        .stderr(predicate::str::contains("panicked").not());
+    temp.child("untyped.gsn.svg")
+        .assert(predicate::path::missing());
+    temp.close()?;
+    Ok(())
+}
+
+#[test]
+fn contract_arch_view() -> Result<()> {
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    let temp = assert_fs::TempDir::new()?;
+    temp.copy_from(".", &["examples/contract/*.yaml"])?;
+    let output_file = temp.child("examples/contract/architecture.svg");
+    cmd.arg("examples/contract/oem.gsn.yaml")
+        .arg("-o=examples/contract")
+        .arg("-E")
+        .arg("-F")
+        .arg("-G")
+        .current_dir(&temp);
+    cmd.assert().success();
+    assert_files_equal(
+        &output_file,
+        Path::new("examples/contract/architecture.svg"),
+    )?;
+    temp.close()?;
+    Ok(())
+}
+
 #[test]
 fn uses_circle_detection() -> Result<()> {
     let mut cmd = Command::new(cargo::cargo_bin!());
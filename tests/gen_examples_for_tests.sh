@@ -35,6 +35,19 @@ cp examples/example.gsn.svg examples/example.gsn_dark.svg
 ./target/release/gsn2x -G -E examples/dialectic/first.gsn.yaml
 ./target/release/gsn2x -G -E examples/dialectic/second.gsn.yaml
 
+# pattern example
+./target/release/gsn2x -G -E examples/pattern/hazard_avoidance.gsn.yaml
+./target/release/gsn2x examples/pattern/hazard_avoidance.gsn.yaml --instantiate=examples/pattern/brake_system.binding.yaml
+
+# module interface example
+./target/release/gsn2x -G -E -A -F examples/interface/system.gsn.yaml examples/interface/component.gsn.yaml
+
+# module reference example
+./target/release/gsn2x -G -E -A -F examples/module_reference/system.gsn.yaml
+
+# contract example
+./target/release/gsn2x -G -E -F -o=examples/contract examples/contract/oem.gsn.yaml
+
 # bullet lists
 ./target/release/gsn2x -G -E examples/bullet_lists.gsn.yaml
 
@@ -128,3 +128,36 @@ fn yaml_dump() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn pattern_instantiation() -> Result<()> {
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    let temp = assert_fs::TempDir::new()?;
+    temp.copy_from(
+        ".",
+        &[
+            "examples/pattern/hazard_avoidance.gsn.yaml",
+            "examples/pattern/brake_system.binding.yaml",
+        ],
+    )?;
+    cmd.arg("examples/pattern/hazard_avoidance.gsn.yaml")
+        .arg("--instantiate=examples/pattern/brake_system.binding.yaml")
+        .current_dir(&temp);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::is_empty());
+
+    assert_files_equal(
+        &temp.child("examples/pattern/brake_system.gsn.yaml"),
+        std::path::Path::new("examples/pattern/brake_system.gsn.yaml"),
+    )?;
+
+    let mut cmd = Command::new(cargo::cargo_bin!());
+    cmd.arg("-c")
+        .arg("examples/pattern/brake_system.gsn.yaml")
+        .current_dir(&temp);
+    cmd.assert().success();
+
+    Ok(())
+}